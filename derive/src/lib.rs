@@ -2,7 +2,7 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Field};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Field, GenericParam};
 
 #[proc_macro_derive(Context)]
 pub fn derive_context(input: TokenStream) -> TokenStream {
@@ -17,17 +17,58 @@ pub fn derive_context(input: TokenStream) -> TokenStream {
         .iter()
         .map(|Field { ident, .. }| {
             quote! {
-                stringify!(#ident) => self.#ident.get_from_context(path),
+                stringify!(#ident) => self.#ident.get_from_context(path).map_err(|e| e.within(stringify!(#ident))),
             }
         })
         .collect::<Vec<_>>();
 
+    let ref_arms = data
+        .fields
+        .iter()
+        .map(|Field { ident, .. }| {
+            quote! {
+                stringify!(#ident) => self.#ident.get_ref_from_context(path).map_err(|e| e.within(stringify!(#ident))),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let refs_arms = data
+        .fields
+        .iter()
+        .map(|Field { ident, .. }| {
+            quote! {
+                stringify!(#ident) => Some(self.#ident.get_refs_from_context(paths)),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let fields = data
+        .fields
+        .iter()
+        .map(|Field { ident, ty, .. }| {
+            quote! {
+                kimari::schema::Field {
+                    name: stringify!(#ident),
+                    schema: <#ty as kimari::context::Context>::schema,
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut generics = input.generics.clone();
+    for param in &mut generics.params {
+        if let GenericParam::Type(t) = param {
+            t.bounds.push(parse_quote!(kimari::context::Context));
+        }
+    }
+
     let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let output = quote! {
-        impl kimari::context::Context for #name {
-            fn get_from_context<'a, I>(&self, path: I) -> Result<kimari::Value, kimari::context::Error>
+        impl #impl_generics kimari::context::Context for #name #ty_generics #where_clause {
+            fn get_from_context<'__path, I>(&self, path: I) -> Result<kimari::Value, kimari::context::Error>
             where
-                I: IntoIterator<Item = &'a str>,
+                I: IntoIterator<Item = &'__path str>,
             {
                 let mut path = path.into_iter();
                 let name = path.next();
@@ -36,9 +77,35 @@ pub fn derive_context(input: TokenStream) -> TokenStream {
                         #(#arms)*
                         _ => return Err(kimari::context::Error::UnexpectedPath([s].into()))
                     },
-                    _ => unimplemented!("Value representation of structs is not supported yet."),
+                    _ => Err(kimari::context::Error::UnsupportedValue([].into(), "struct".to_string())),
+                }
+            }
+
+            fn get_ref_from_context<'__path, I>(&self, path: I) -> Result<kimari::ValueRef<'_>, kimari::context::Error>
+            where
+                I: IntoIterator<Item = &'__path str>,
+            {
+                let mut path = path.into_iter();
+                let name = path.next();
+                match name {
+                    Some(s) => match s {
+                        #(#ref_arms)*
+                        _ => return Err(kimari::context::Error::UnexpectedPath([s].into()))
+                    },
+                    _ => Err(kimari::context::Error::UnsupportedValue([].into(), "struct".to_string())),
                 }
             }
+
+            fn get_refs_from_context(&self, paths: &[&[&str]]) -> Vec<Result<kimari::ValueRef<'_>, kimari::context::Error>> {
+                kimari::context::get_refs_from_fields(self, paths, |name, paths| match name {
+                    #(#refs_arms)*
+                    _ => None,
+                })
+            }
+
+            fn schema() -> kimari::schema::Schema {
+                kimari::schema::Schema::Struct(vec![#(#fields)*])
+            }
         }
     };
 
@@ -0,0 +1,421 @@
+//! Static analysis of rules, finding conditions that are contradictory, tautological, duplicated or shadowed.
+//!
+//! The analysis is conservative: it reports only what it can prove from the literals of the operators,
+//! so a rule without findings may still be unsatisfiable for reasons that depend on the context.
+
+use std::fmt::{Display, Formatter};
+
+use crate::network::NetworkSet;
+use crate::operator::{All, Location, Operator};
+use crate::rule::{Rule, Rules};
+
+/// Issue found in a condition or a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// The condition is never satisfied by any context.
+    Unsatisfiable,
+
+    /// The condition is satisfied by any context where its attributes are present with the types it expects.
+    Tautology,
+
+    /// The operator is the same as its sibling at the location.
+    Duplicate(Location),
+
+    /// The rule is satisfied only if the named rule is, so it never applies under first-applicable combining.
+    Subsumed(String),
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsatisfiable => write!(f, "condition is never satisfied"),
+            Self::Tautology => write!(f, "condition is always satisfied"),
+            Self::Duplicate(l) => write!(f, "operator duplicates the one at {}", l),
+            Self::Subsumed(r) => write!(f, "rule is shadowed by rule {}", r),
+        }
+    }
+}
+
+/// Issue found in a rule, with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: String,
+
+    /// Whether the issue is in the target of the rule rather than its operator.
+    pub in_target: bool,
+    pub location: Location,
+    pub issue: Issue,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Rule {}: {} (at {}{})",
+            self.rule,
+            self.issue,
+            if self.in_target { "target " } else { "" },
+            self.location,
+        )
+    }
+}
+
+/// Analyzes the rules, reporting the findings in the order of the rules.
+/// Rules are compared with the rules before them in the order they are defined, as they are combined.
+pub fn analyze(rules: &Rules) -> Vec<Finding> {
+    let mut findings = vec![];
+    let mut preceding: Vec<(&String, Operator)> = vec![];
+    for (name, rule) in rules.iter() {
+        let mut finding = |in_target, location, issue| {
+            findings.push(Finding {
+                rule: name.clone(),
+                in_target,
+                location,
+                issue,
+            })
+        };
+
+        let targets = rule.target().map(|t| (true, t));
+        for (in_target, operator) in targets.into_iter().chain([(false, rule.operator())]) {
+            inspect(operator, Location::default(), &mut |l, i| {
+                finding(in_target, l, i)
+            });
+        }
+
+        let condition = condition(rule);
+        if let Some(t) = rule.target() {
+            if !unsatisfiable(t) && !unsatisfiable(rule.operator()) && unsatisfiable(&condition) {
+                finding(false, Location::default(), Issue::Unsatisfiable);
+            }
+        }
+
+        if !unsatisfiable(&condition) {
+            if let Some((by, _)) = preceding.iter().find(|(_, c)| implies(&condition, c)) {
+                finding(false, Location::default(), Issue::Subsumed(by.to_string()));
+            }
+        }
+
+        preceding.push((name, condition));
+    }
+
+    findings
+}
+
+/// Condition of the rule to be satisfied, which is both the target and the operator.
+fn condition(rule: &Rule) -> Operator {
+    match rule.target() {
+        Some(t) => Operator::All(All {
+            operators: vec![t.clone(), rule.operator().clone()],
+        }),
+        _ => rule.operator().clone(),
+    }
+}
+
+/// Reports the issues in the operator and its descendants.
+/// A condition is reported only where it is caused, not on its ancestors nor its descendants.
+fn inspect<F>(operator: &Operator, location: Location, report: &mut F)
+where
+    F: FnMut(Location, Issue),
+{
+    let children = match operator {
+        Operator::All(o) => o.operators.as_slice(),
+        Operator::Any(o) => o.operators.as_slice(),
+        Operator::Not(o) => std::slice::from_ref(o.operator.as_ref()),
+        _ => &[],
+    };
+
+    if unsatisfiable(operator) && !children.iter().any(unsatisfiable) {
+        report(location.clone(), Issue::Unsatisfiable);
+    }
+    if tautology(operator) && !children.iter().any(tautology) {
+        report(location.clone(), Issue::Tautology);
+    }
+
+    for (i, child) in children.iter().enumerate() {
+        if let Some(j) = children[..i].iter().position(|c| c == child) {
+            report(location.child(i), Issue::Duplicate(location.child(j)));
+        }
+        inspect(child, location.child(i), report);
+    }
+}
+
+/// Operators that all have to be satisfied for the operator to be, flattening nested `all`.
+fn conjuncts(operator: &Operator) -> Vec<&Operator> {
+    match operator {
+        Operator::All(o) => o.operators.iter().flat_map(conjuncts).collect(),
+        o => vec![o],
+    }
+}
+
+/// Operators any of which has to be satisfied for the operator to be, flattening nested `any`.
+fn disjuncts(operator: &Operator) -> Vec<&Operator> {
+    match operator {
+        Operator::Any(o) => o.operators.iter().flat_map(disjuncts).collect(),
+        o => vec![o],
+    }
+}
+
+/// Whether the operator is provably never satisfied.
+fn unsatisfiable(operator: &Operator) -> bool {
+    match operator {
+        Operator::All(_) => {
+            let conjuncts = conjuncts(operator);
+            conjuncts.iter().any(|c| unsatisfiable(c))
+                || pairs(&conjuncts).any(|(a, b)| contradicts(a, b))
+        }
+        Operator::Any(o) => o.operators.iter().all(unsatisfiable),
+        Operator::InNetwork(o) => o.networks.blocks().is_empty(),
+        Operator::Bucket(o) => o.from >= o.to,
+        #[cfg(feature = "chrono")]
+        Operator::DayOfWeek(o) => o.days.is_empty(),
+        #[cfg(feature = "chrono")]
+        Operator::TimeOfDay(o) => o.from == o.to,
+        _ => false,
+    }
+}
+
+/// Whether the operator is provably satisfied whenever the attributes it looks up are present with the expected types.
+fn tautology(operator: &Operator) -> bool {
+    match operator {
+        Operator::All(o) => o.operators.iter().all(tautology),
+        Operator::Any(_) => {
+            let disjuncts = disjuncts(operator);
+            disjuncts.iter().any(|d| tautology(d))
+                || pairs(&disjuncts).any(|(a, b)| complements(a, b))
+        }
+        Operator::Bucket(o) => o.from == 0 && o.to >= 100,
+        #[cfg(feature = "chrono")]
+        Operator::DayOfWeek(o) => {
+            o.days
+                .iter()
+                .map(|d| d.num_days_from_monday())
+                .collect::<std::collections::BTreeSet<_>>()
+                .len()
+                == 7
+        }
+        _ => false,
+    }
+}
+
+/// Whether the operators are provably never satisfied together.
+fn contradicts(a: &Operator, b: &Operator) -> bool {
+    match (a, b) {
+        (Operator::Not(a), b) | (b, Operator::Not(a)) if *a.operator == *b => true,
+        (Operator::Equals(a), Operator::Equals(b)) => a.r#where == b.r#where && a.to != b.to,
+        (Operator::Equals(a), Operator::NotEquals(b))
+        | (Operator::NotEquals(b), Operator::Equals(a)) => a.r#where == b.r#where && a.to == b.to,
+        (Operator::InNetwork(a), Operator::NotInNetwork(b))
+        | (Operator::NotInNetwork(b), Operator::InNetwork(a)) => {
+            a.r#where == b.r#where && covers(&b.networks, &a.networks)
+        }
+        (Operator::Bucket(a), Operator::Bucket(b)) => {
+            a.r#where == b.r#where && a.salt == b.salt && (a.to <= b.from || b.to <= a.from)
+        }
+        #[cfg(feature = "chrono")]
+        (Operator::Before(a), Operator::After(b)) | (Operator::After(b), Operator::Before(a)) => {
+            a.r#where == b.r#where && b.to >= a.to
+        }
+        _ => false,
+    }
+}
+
+/// Whether either of the operators is provably satisfied whenever their attributes are present with the expected types.
+fn complements(a: &Operator, b: &Operator) -> bool {
+    match (a, b) {
+        (Operator::Not(a), b) | (b, Operator::Not(a)) if *a.operator == *b => true,
+        (Operator::Equals(a), Operator::NotEquals(b))
+        | (Operator::NotEquals(b), Operator::Equals(a)) => a.r#where == b.r#where && a.to == b.to,
+        (Operator::InNetwork(a), Operator::NotInNetwork(b))
+        | (Operator::NotInNetwork(b), Operator::InNetwork(a)) => {
+            a.r#where == b.r#where && covers(&a.networks, &b.networks)
+        }
+        (Operator::Bucket(a), Operator::Bucket(b)) => {
+            let (lower, upper) = match a.from <= b.from {
+                true => (a, b),
+                false => (b, a),
+            };
+            a.r#where == b.r#where
+                && a.salt == b.salt
+                && lower.from == 0
+                && lower.to >= upper.from
+                && lower.to.max(upper.to) >= 100
+        }
+        _ => false,
+    }
+}
+
+/// Whether the operator `a` is provably satisfied only if `b` is.
+fn implies(a: &Operator, b: &Operator) -> bool {
+    if a == b {
+        return true;
+    }
+
+    match (a, b) {
+        (Operator::Any(a), b) => a.operators.iter().all(|o| implies(o, b)),
+        (a, Operator::All(b)) => b.operators.iter().all(|o| implies(a, o)),
+        (Operator::All(a), b) => a.operators.iter().any(|o| implies(o, b)),
+        (a, Operator::Any(b)) => b.operators.iter().any(|o| implies(a, o)),
+        (Operator::Not(a), Operator::Not(b)) => implies(&b.operator, &a.operator),
+        (Operator::Equals(a), Operator::NotEquals(b)) => a.r#where == b.r#where && a.to != b.to,
+        (Operator::InNetwork(a), Operator::InNetwork(b)) => {
+            a.r#where == b.r#where && covers(&b.networks, &a.networks)
+        }
+        (Operator::NotInNetwork(a), Operator::NotInNetwork(b)) => {
+            a.r#where == b.r#where && covers(&a.networks, &b.networks)
+        }
+        (Operator::Bucket(a), Operator::Bucket(b)) => {
+            a.r#where == b.r#where && a.salt == b.salt && b.from <= a.from && a.to <= b.to
+        }
+        #[cfg(feature = "chrono")]
+        (Operator::Before(a), Operator::Before(b)) => a.r#where == b.r#where && a.to <= b.to,
+        #[cfg(feature = "chrono")]
+        (Operator::After(a), Operator::After(b)) => a.r#where == b.r#where && a.to >= b.to,
+        #[cfg(feature = "chrono")]
+        (Operator::DayOfWeek(a), Operator::DayOfWeek(b)) => {
+            a.r#where == b.r#where
+                && a.timezone == b.timezone
+                && a.days.iter().all(|d| b.days.contains(d))
+        }
+        _ => false,
+    }
+}
+
+/// Whether every block of `inner` is within any block of `outer`.
+fn covers(outer: &NetworkSet, inner: &NetworkSet) -> bool {
+    inner.blocks().iter().all(|i| {
+        outer
+            .blocks()
+            .iter()
+            .any(|o| o.prefix() <= i.prefix() && o.contains(i.address()))
+    })
+}
+
+fn pairs<'a, 'b>(
+    operators: &'b [&'a Operator],
+) -> impl Iterator<Item = (&'a Operator, &'a Operator)> + 'b {
+    operators
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, a)| operators[i + 1..].iter().map(move |b| (*a, *b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(yaml: &str) -> Vec<String> {
+        let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
+        super::analyze(&rules)
+            .iter()
+            .map(|f| f.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn unsatisfiable() {
+        // language=yaml
+        let yaml = r#"
+        one_and_two:
+          type: all
+          operators:
+            - type: equals
+              where: x
+              to: 1
+            - type: all
+              operators:
+                - type: equals
+                  where: x
+                  to: 2
+        nothing:
+          type: any
+          operators: []
+        targeted:
+          target:
+            type: equals
+            where: x
+            to: 1
+          type: notEquals
+          where: x
+          to: 1
+        "#;
+
+        assert_eq!(
+            vec![
+                "Rule one_and_two: condition is never satisfied (at root)",
+                "Rule nothing: condition is never satisfied (at root)",
+                "Rule targeted: condition is never satisfied (at root)",
+            ],
+            analyze(yaml),
+        );
+    }
+
+    #[test]
+    fn tautology_and_duplicate() {
+        // language=yaml
+        let yaml = r#"
+        everyone:
+          type: any
+          operators:
+            - type: equals
+              where: x
+              to: 1
+            - type: bucket
+              where: id
+              to: 50
+            - type: notEquals
+              where: x
+              to: 1
+            - type: bucket
+              where: id
+              to: 50
+        "#;
+
+        assert_eq!(
+            vec![
+                "Rule everyone: condition is always satisfied (at root)",
+                "Rule everyone: operator duplicates the one at operators[1] (at operators[3])",
+            ],
+            analyze(yaml),
+        );
+    }
+
+    #[test]
+    fn subsumed() {
+        // language=yaml
+        let yaml = r#"
+        a_internal:
+          type: inNetwork
+          where: ip
+          networks: [10.0.0.0/8]
+        b_office:
+          type: all
+          operators:
+            - type: inNetwork
+              where: ip
+              networks: [10.1.0.0/16]
+            - type: equals
+              where: role
+              to: admin
+        c_rollout:
+          type: bucket
+          where: id
+          to: 10
+        d_canary:
+          target:
+            type: equals
+            where: role
+            to: admin
+          type: bucket
+          where: id
+          to: 5
+        "#;
+
+        assert_eq!(
+            vec![
+                "Rule b_office: rule is shadowed by rule a_internal (at root)",
+                "Rule d_canary: rule is shadowed by rule c_rollout (at root)",
+            ],
+            analyze(yaml),
+        );
+    }
+}
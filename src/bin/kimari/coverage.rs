@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use kimari::coverage::Coverage;
+use kimari::{Environment, Rules};
+
+use crate::input::{load, load_contexts, STDIN};
+
+/// Evaluates the rules against a corpus of contexts, reporting the conditions never evaluated or never decisive.
+#[derive(clap::Args)]
+pub struct Args {
+    /// File of the rules, written in JSON or YAML.
+    rules: PathBuf,
+
+    /// Files of the contexts, written in JSON or YAML; `-` reads the standard input.
+    #[arg(default_value = STDIN)]
+    contexts: Vec<PathBuf>,
+
+    /// Format of the report.
+    #[arg(long, value_enum, default_value = "text")]
+    format: Format,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Lcov,
+}
+
+/// Measures the coverage, returning whether no rules raised errors.
+pub fn run(args: &Args, out: &mut dyn Write, _: &mut dyn Write) -> Result<bool, Box<dyn Error>> {
+    let rules = load::<Rules>(&args.rules)?;
+    let documents = load_contexts(&args.contexts)?;
+
+    let mut coverage = Coverage::new(&rules);
+    for document in &documents {
+        coverage.record(&rules, &document.context, &Environment::default());
+    }
+
+    match args.format {
+        Format::Text => writeln!(out, "{}", coverage)?,
+        Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(&coverage)?)?,
+        Format::Lcov => write!(
+            out,
+            "{}",
+            coverage.to_lcov(&args.rules.display().to_string())
+        )?,
+    }
+
+    Ok(coverage.rules.values().all(|r| r.errors == 0))
+}
@@ -0,0 +1,52 @@
+use std::error::Error;
+use std::io::Write;
+use std::path::PathBuf;
+
+use kimari::{diff, Environment, Rules};
+
+use crate::input::{load, load_contexts};
+
+/// Compares two versions of the rules, printing the changes of the rules and their conditions.
+#[derive(clap::Args)]
+pub struct Args {
+    /// File of the old rules, written in JSON or YAML.
+    old: PathBuf,
+
+    /// File of the new rules, written in JSON or YAML.
+    new: PathBuf,
+
+    /// Files of sample contexts to evaluate both versions against, printing the contexts whose outcomes change.
+    #[arg(long, num_args = 1..)]
+    contexts: Vec<PathBuf>,
+}
+
+/// Runs the comparison, returning whether the rules are unchanged.
+pub fn run(args: &Args, out: &mut dyn Write, _: &mut dyn Write) -> Result<bool, Box<dyn Error>> {
+    let old = load::<Rules>(&args.old)?;
+    let new = load::<Rules>(&args.new)?;
+
+    let changes = diff::diff(&old, &new);
+    for change in &changes {
+        writeln!(out, "{}", change)?;
+    }
+
+    if args.contexts.is_empty() {
+        return Ok(changes.is_empty());
+    }
+
+    let documents = load_contexts(&args.contexts)?;
+    let contexts = documents.iter().map(|d| &d.context).collect::<Vec<_>>();
+    let outcomes = diff::outcome_changes(&old, &new, contexts, &Environment::default());
+    if !outcomes.is_empty() {
+        writeln!(out, "\nChanged outcomes:")?;
+    }
+    for change in &outcomes {
+        writeln!(
+            out,
+            "{}: rule {}: {} -> {}",
+            documents[change.context].label, change.rule, change.old, change.new,
+        )?;
+    }
+
+    Ok(changes.is_empty())
+}
@@ -0,0 +1,184 @@
+use std::error::Error;
+use std::io::Write;
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use kimari::{ErrorPolicy, Rules};
+
+use crate::input::{load, load_contexts, Document, STDIN};
+
+/// Evaluates the rules against each context, printing the names of the matched rules.
+#[derive(clap::Args)]
+pub struct Args {
+    /// File of the rules, written in JSON or YAML.
+    rules: PathBuf,
+
+    /// Files of the contexts, written in JSON or YAML; `-` reads the standard input.
+    #[arg(default_value = STDIN)]
+    contexts: Vec<PathBuf>,
+
+    /// Prints the decision of every rule instead of the names of the matched rules.
+    #[arg(long, conflicts_with = "explain")]
+    decisions: bool,
+
+    /// Prints how the decision of every rule was made, as JSON.
+    #[arg(long)]
+    explain: bool,
+}
+
+/// Runs the evaluation, returning whether it succeeded without any errors.
+pub fn run(args: &Args, out: &mut dyn Write, err: &mut dyn Write) -> Result<bool, Box<dyn Error>> {
+    let rules = load::<Rules>(&args.rules)?;
+    let documents = load_contexts(&args.contexts)?;
+
+    let mut succeeded = true;
+    for document in &documents {
+        if documents.len() > 1 {
+            writeln!(out, "# {}", document.label)?;
+        }
+
+        succeeded &= match (args.decisions, args.explain) {
+            (_, true) => explain(&rules, document, out, err)?,
+            (true, _) => decisions(&rules, document, out, err)?,
+            _ => matched(&rules, document, out, err)?,
+        };
+    }
+
+    Ok(succeeded)
+}
+
+fn matched(
+    rules: &Rules,
+    document: &Document,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<bool, Box<dyn Error>> {
+    let evaluation = rules.evaluate(&document.context, ErrorPolicy::Collect)?;
+    for (name, _) in &evaluation.matched {
+        writeln!(out, "{}", name)?;
+    }
+    for (_, e) in &evaluation.errors {
+        writeln!(err, "error: {}: {}", document.label, e)?;
+    }
+
+    Ok(!evaluation.has_errors())
+}
+
+fn decisions(
+    rules: &Rules,
+    document: &Document,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<bool, Box<dyn Error>> {
+    let mut succeeded = true;
+    for (name, rule) in rules.iter() {
+        match rule.is_satisfied_by(&document.context) {
+            Ok(d) => writeln!(out, "{}: {}", name, d)?,
+            Err(e) => {
+                writeln!(err, "error: {}: {}", document.label, e.in_rule(name))?;
+                succeeded = false;
+            }
+        }
+    }
+
+    Ok(succeeded)
+}
+
+fn explain(
+    rules: &Rules,
+    document: &Document,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> Result<bool, Box<dyn Error>> {
+    let mut succeeded = true;
+    let mut explanations = IndexMap::new();
+    for (name, rule) in rules.iter() {
+        let explanation = rule.explain(&document.context);
+        if let Some(e) = explanation.first_error() {
+            writeln!(
+                err,
+                "error: {}: Error in rule {}: {}",
+                document.label, name, e
+            )?;
+            succeeded = false;
+        }
+
+        explanations.insert(name.as_str(), explanation);
+    }
+
+    writeln!(out, "{}", serde_json::to_string_pretty(&explanations)?)?;
+
+    Ok(succeeded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::parse_contexts;
+
+    // language=yaml
+    const RULES: &str = r#"
+foo_is_abc:
+  type: equals
+  where: foo
+  to: abc
+bar_is_123:
+  type: equals
+  where: bar.baz
+  to: 123
+"#;
+
+    type Report =
+        fn(&Rules, &Document, &mut dyn Write, &mut dyn Write) -> Result<bool, Box<dyn Error>>;
+
+    fn run(f: Report, context: &str) -> (bool, String, String) {
+        let rules = serde_yaml::from_str::<Rules>(RULES).unwrap();
+        let document = parse_contexts("ctx.yaml", context).unwrap().remove(0);
+        let (mut out, mut err) = (vec![], vec![]);
+        let succeeded = f(&rules, &document, &mut out, &mut err).unwrap();
+
+        (
+            succeeded,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn matched() {
+        assert_eq!(
+            (true, "foo_is_abc\n".to_string(), "".to_string()),
+            run(super::matched, "{ foo: abc, bar: { baz: 456 } }"),
+        );
+        assert_eq!(
+            (
+                false,
+                "foo_is_abc\n".to_string(),
+                "error: ctx.yaml: Error in rule bar_is_123: Unexpected context: Unexpected index in an array: bar.baz (invalid digit found in string)\n".to_string(),
+            ),
+            run(super::matched, "{ foo: abc, bar: [1] }"),
+        );
+    }
+
+    #[test]
+    fn decisions() {
+        assert_eq!(
+            (
+                true,
+                "foo_is_abc: indeterminate\nbar_is_123: accept\n".to_string(),
+                "".to_string(),
+            ),
+            run(super::decisions, "{ bar: { baz: 123 } }"),
+        );
+    }
+
+    #[test]
+    fn explain() {
+        let (succeeded, out, _) = run(super::explain, "{ foo: abc, bar: { baz: 123 } }");
+        let json = serde_json::from_str::<serde_json::Value>(&out).unwrap();
+
+        assert!(succeeded);
+        assert_eq!("accept", json["foo_is_abc"]["decision"]);
+        assert_eq!("bar.baz", json["bar_is_123"]["where"]);
+    }
+}
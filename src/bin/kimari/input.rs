@@ -0,0 +1,109 @@
+use std::error::Error;
+use std::fs::read_to_string;
+use std::io::{stdin, Read};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_yaml::Value as Yaml;
+
+/// Path that reads from the standard input instead of a file.
+pub const STDIN: &str = "-";
+
+/// Reads the file, or the standard input if the path is `-`.
+pub fn read(path: &Path) -> Result<String, Box<dyn Error>> {
+    if path == Path::new(STDIN) {
+        let mut s = String::new();
+        stdin().read_to_string(&mut s)?;
+        return Ok(s);
+    }
+
+    read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+/// Reads a document from the file, written in either JSON or YAML.
+pub fn load<T>(path: &Path) -> Result<T, Box<dyn Error>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    serde_yaml::from_str(&read(path)?).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+/// Context document labelled by where it came from.
+pub struct Document {
+    pub label: String,
+    pub context: Yaml,
+}
+
+/// Reads all context documents from the files, splitting YAML streams into documents.
+/// Standard input is read once at most, as it cannot be read again.
+pub fn load_contexts(paths: &[PathBuf]) -> Result<Vec<Document>, Box<dyn Error>> {
+    if paths
+        .iter()
+        .filter(|p| p.as_path() == Path::new(STDIN))
+        .count()
+        > 1
+    {
+        return Err("standard input cannot be read more than once".into());
+    }
+
+    let mut documents = vec![];
+    for path in paths {
+        let source = match path.as_path() == Path::new(STDIN) {
+            true => "<stdin>".to_string(),
+            false => path.display().to_string(),
+        };
+        documents.extend(parse_contexts(&source, &read(path)?)?);
+    }
+
+    Ok(documents)
+}
+
+/// Parses the context documents in the content, labelling them with the source and their positions if multiple.
+pub fn parse_contexts(source: &str, content: &str) -> Result<Vec<Document>, Box<dyn Error>> {
+    let contexts = serde_yaml::Deserializer::from_str(content)
+        .map(Yaml::deserialize)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {}", source, e))?;
+
+    let multiple = contexts.len() > 1;
+    Ok(contexts
+        .into_iter()
+        .enumerate()
+        .map(|(i, context)| Document {
+            label: match multiple {
+                true => format!("{}#{}", source, i),
+                false => source.to_string(),
+            },
+            context,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parse_contexts() {
+        // language=yaml
+        let yaml = r#"
+foo: 1
+---
+{ "foo": 2 }
+"#;
+
+        let documents = super::parse_contexts("a.yaml", yaml).unwrap();
+
+        assert_eq!(
+            vec!["a.yaml#0", "a.yaml#1"],
+            documents
+                .iter()
+                .map(|d| d.label.as_str())
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(Some(2), documents[1].context["foo"].as_i64());
+        assert_eq!(
+            "a.json",
+            super::parse_contexts("a.json", r#"{ "foo": 1 }"#).unwrap()[0].label,
+        );
+        assert!(super::parse_contexts("b.yaml", "foo: [").is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Command-line tool to evaluate rule files against contexts written in JSON or YAML.
+
+mod coverage;
+mod diff;
+mod eval;
+mod input;
+mod test;
+
+use std::io::{stderr, stdout};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+/// Evaluates kimari rules without writing Rust.
+///
+/// Exits with 1 if any rule raised an error, any fixture failed, the compared rules differ,
+/// or any file could not be read, so that it can be used in scripts.
+#[derive(Parser)]
+#[command(name = "kimari", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Coverage(coverage::Args),
+    Diff(diff::Args),
+    Eval(eval::Args),
+    Test(test::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let (mut out, mut err) = (stdout().lock(), stderr().lock());
+
+    let result = match cli.command {
+        Command::Coverage(args) => coverage::run(&args, &mut out, &mut err),
+        Command::Diff(args) => diff::run(&args, &mut out, &mut err),
+        Command::Eval(args) => eval::run(&args, &mut out, &mut err),
+        Command::Test(args) => test::run(&args, &mut out, &mut err),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::io::Write;
+use std::path::PathBuf;
+
+use kimari::fixture::{self, Fixture};
+use kimari::{Environment, Rules};
+use serde_yaml::Value as Yaml;
+
+use crate::input::load;
+
+/// Runs the test fixtures against the rules, reporting the mismatches of the failed ones.
+#[derive(clap::Args)]
+pub struct Args {
+    /// File of the rules, written in JSON or YAML.
+    rules: PathBuf,
+
+    /// Files of the fixtures, each of which is a list of a name, a context, an optional `now` and the expected `matched` rules and/or `decisions`.
+    #[arg(required = true)]
+    fixtures: Vec<PathBuf>,
+}
+
+/// Runs the fixtures, returning whether all of them passed.
+pub fn run(args: &Args, out: &mut dyn Write, _: &mut dyn Write) -> Result<bool, Box<dyn Error>> {
+    let rules = load::<Rules>(&args.rules)?;
+
+    let mut fixtures = vec![];
+    for path in &args.fixtures {
+        let loaded = load::<Vec<Fixture<Yaml>>>(path)?;
+        fixtures.extend(loaded.into_iter().map(|f| match args.fixtures.len() {
+            1 => f,
+            _ => Fixture {
+                name: format!("{}: {}", path.display(), f.name),
+                ..f
+            },
+        }));
+    }
+
+    let report = fixture::run(&rules, &fixtures, &Environment::default());
+    writeln!(out, "{}", report)?;
+
+    Ok(report.passed())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use super::*;
+
+    // language=yaml
+    const RULES: &str = r#"
+foo_is_abc:
+  type: equals
+  where: foo
+  to: abc
+"#;
+
+    // language=yaml
+    const PASSING: &str = r#"
+- name: abc
+  context: { foo: abc }
+  matched: [foo_is_abc]
+"#;
+
+    // language=yaml
+    const FAILING: &str = r#"
+- name: def
+  context: { foo: def }
+  decisions:
+    foo_is_abc: accept
+"#;
+
+    /// Writes the files into a directory of the test, returning their paths.
+    fn files(test: &str, files: &[(&str, &str)]) -> Vec<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("kimari-{}-{}", test, std::process::id()));
+        create_dir_all(&dir).unwrap();
+
+        files
+            .iter()
+            .map(|(name, content)| {
+                let path = dir.join(name);
+                write(&path, content).unwrap();
+                path
+            })
+            .collect()
+    }
+
+    fn run(rules: PathBuf, fixtures: Vec<PathBuf>) -> (bool, String) {
+        let mut out = vec![];
+        let passed = super::run(&Args { rules, fixtures }, &mut out, &mut vec![]).unwrap();
+
+        (passed, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn passing() {
+        let mut paths = files(
+            "passing",
+            &[("rules.yaml", RULES), ("fixtures.yaml", PASSING)],
+        );
+        let fixtures = paths.split_off(1);
+
+        assert_eq!(
+            (true, "ok   abc\n\n1 passed, 0 failed\n".to_string()),
+            run(paths.remove(0), fixtures),
+        );
+    }
+
+    #[test]
+    fn failing() {
+        let mut paths = files(
+            "failing",
+            &[
+                ("rules.yaml", RULES),
+                ("passing.yaml", PASSING),
+                ("failing.yaml", FAILING),
+            ],
+        );
+        let fixtures = paths.split_off(1);
+        let expected = format!(
+            "ok   {}: abc\nFAIL {}: def\n    decision of foo_is_abc differs:\n      - accept\n      + reject\n\n1 passed, 1 failed\n",
+            fixtures[0].display(),
+            fixtures[1].display(),
+        );
+
+        assert_eq!((false, expected), run(paths.remove(0), fixtures));
+    }
+}
@@ -0,0 +1,158 @@
+use std::convert::Infallible;
+
+use serde::{Deserialize, Serialize};
+
+use crate::decision::Decision;
+
+/// Effect of a rule when it is satisfied by the context.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Effect {
+    #[default]
+    Permit,
+    Deny,
+}
+
+impl Effect {
+    pub fn is_permit(&self) -> bool {
+        *self == Self::Permit
+    }
+
+    /// Applies the effect to the decision of a rule.
+    ///
+    /// If the rule is satisfied, `Permit` results in `Accept` and `Deny` results in `Reject`.
+    /// If the rule is not satisfied, it does not apply to the context, so `NotApplicable` is returned.
+    pub fn apply(self, decision: Decision) -> Decision {
+        match decision {
+            Decision::Accept => match self {
+                Self::Permit => Decision::Accept,
+                Self::Deny => Decision::Reject,
+            },
+            Decision::Reject | Decision::NotApplicable => Decision::NotApplicable,
+            Decision::Indeterminate => Decision::Indeterminate,
+        }
+    }
+}
+
+/// Algorithm to fold the effects of rules into a final decision.
+/// `Accept` stands for permit and `Reject` stands for deny.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Combining {
+    /// Any deny wins, then any indeterminate, then any permit.
+    DenyOverrides,
+
+    /// Any permit wins, then any indeterminate, then any deny.
+    PermitOverrides,
+
+    /// The first rule that applies to the context wins.
+    FirstApplicable,
+
+    /// Every rule must permit, and any rule that does not apply denies.
+    /// If no rules apply to the context, the result is not applicable.
+    Unanimous,
+}
+
+impl Combining {
+    pub fn try_combine<I, E>(self, iter: I) -> Result<Decision, E>
+    where
+        I: IntoIterator<Item = Result<Decision, E>>,
+    {
+        let (mut accepted, mut rejected, mut indeterminate, mut not_applicable) =
+            (false, false, false, false);
+
+        for decision in iter {
+            let decision = decision?;
+            match (self, decision) {
+                (Self::DenyOverrides, Decision::Reject) => return Ok(Decision::Reject),
+                (Self::PermitOverrides, Decision::Accept) => return Ok(Decision::Accept),
+                (Self::FirstApplicable, Decision::NotApplicable) => {}
+                (Self::FirstApplicable, d) => return Ok(d),
+                (_, Decision::Accept) => accepted = true,
+                (_, Decision::Reject) => rejected = true,
+                (_, Decision::Indeterminate) => indeterminate = true,
+                (_, Decision::NotApplicable) => not_applicable = true,
+            }
+        }
+
+        Ok(match self {
+            Self::DenyOverrides | Self::PermitOverrides if indeterminate => Decision::Indeterminate,
+            Self::DenyOverrides if accepted => Decision::Accept,
+            Self::PermitOverrides if rejected => Decision::Reject,
+            Self::Unanimous if rejected => Decision::Reject,
+            Self::Unanimous if indeterminate => Decision::Indeterminate,
+            Self::Unanimous if accepted && not_applicable => Decision::Reject,
+            Self::Unanimous if accepted => Decision::Accept,
+            _ => Decision::NotApplicable,
+        })
+    }
+
+    pub fn combine<I>(self, iter: I) -> Decision
+    where
+        I: IntoIterator<Item = Decision>,
+    {
+        self.try_combine::<_, Infallible>(iter.into_iter().map(Ok))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Decision::*;
+
+    #[test]
+    fn effect_apply() {
+        assert_eq!(Accept, Effect::Permit.apply(Accept));
+        assert_eq!(Reject, Effect::Deny.apply(Accept));
+        assert_eq!(NotApplicable, Effect::Permit.apply(Reject));
+        assert_eq!(NotApplicable, Effect::Deny.apply(NotApplicable));
+        assert_eq!(Indeterminate, Effect::Deny.apply(Indeterminate));
+    }
+
+    #[test]
+    fn deny_overrides() {
+        let combining = Combining::DenyOverrides;
+
+        assert_eq!(NotApplicable, combining.combine([]));
+        assert_eq!(NotApplicable, combining.combine([NotApplicable]));
+        assert_eq!(Accept, combining.combine([NotApplicable, Accept]));
+        assert_eq!(Indeterminate, combining.combine([Accept, Indeterminate]));
+        assert_eq!(Reject, combining.combine([Accept, Indeterminate, Reject]));
+    }
+
+    #[test]
+    fn permit_overrides() {
+        let combining = Combining::PermitOverrides;
+
+        assert_eq!(NotApplicable, combining.combine([]));
+        assert_eq!(NotApplicable, combining.combine([NotApplicable]));
+        assert_eq!(Reject, combining.combine([NotApplicable, Reject]));
+        assert_eq!(Indeterminate, combining.combine([Reject, Indeterminate]));
+        assert_eq!(Accept, combining.combine([Reject, Indeterminate, Accept]));
+    }
+
+    #[test]
+    fn first_applicable() {
+        let combining = Combining::FirstApplicable;
+
+        assert_eq!(NotApplicable, combining.combine([]));
+        assert_eq!(NotApplicable, combining.combine([NotApplicable]));
+        assert_eq!(Reject, combining.combine([NotApplicable, Reject, Accept]));
+        assert_eq!(Accept, combining.combine([Accept, Reject]));
+        assert_eq!(Indeterminate, combining.combine([Indeterminate, Accept]));
+    }
+
+    #[test]
+    fn unanimous() {
+        let combining = Combining::Unanimous;
+
+        assert_eq!(NotApplicable, combining.combine([]));
+        assert_eq!(NotApplicable, combining.combine([NotApplicable]));
+        assert_eq!(Accept, combining.combine([Accept, Accept]));
+        assert_eq!(Reject, combining.combine([Accept, NotApplicable]));
+        assert_eq!(Reject, combining.combine([Accept, Indeterminate, Reject]));
+        assert_eq!(Indeterminate, combining.combine([Accept, Indeterminate]));
+    }
+}
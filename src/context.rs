@@ -1,15 +1,58 @@
+use std::borrow::{Borrow, Cow};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
 use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::num::ParseIntError;
+use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use crate::value::Value;
+use crate::schema::Schema;
+use crate::value::{Value, ValueRef};
+
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+mod document;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "semver")]
+mod semver;
+mod serialize;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use serialize::SerializeContext;
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct ErroredPath(String);
+pub struct ErroredPath(Vec<String>);
+
+impl ErroredPath {
+    /// Prepends a segment of the parent to the path.
+    pub fn within(mut self, segment: &str) -> Self {
+        self.0.insert(0, segment.to_string());
+        self
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+}
 
 impl Display for ErroredPath {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .map(|s| s.replace('.', "\\."))
+                .collect::<Vec<_>>()
+                .join("."),
+        )
     }
 }
 
@@ -18,13 +61,7 @@ where
     I: IntoIterator<Item = &'a str>,
 {
     fn from(value: I) -> Self {
-        Self(
-            value
-                .into_iter()
-                .map(|s| s.replace('.', "\\."))
-                .collect::<Vec<_>>()
-                .join("."),
-        )
+        Self(value.into_iter().map(|s| s.to_string()).collect())
     }
 }
 
@@ -33,13 +70,28 @@ pub enum Error {
     #[error("Unexpected path: {0}")]
     UnexpectedPath(ErroredPath),
 
-    #[error("Unexpected index in an array: {0}")]
-    UnexpectedIndex(#[from] ParseIntError),
+    #[error("Unexpected index in an array: {0} ({1})")]
+    UnexpectedIndex(ErroredPath, #[source] ParseIntError),
+
+    #[error("Unsupported value: {0} ({1})")]
+    UnsupportedValue(ErroredPath, String),
 
     #[error("{0}")]
     Other(Box<dyn std::error::Error>),
 }
 
+impl Error {
+    /// Prepends a segment of the parent to the errored path, so that the error has the full path from the root.
+    pub fn within(self, segment: &str) -> Self {
+        match self {
+            Self::UnexpectedPath(p) => Self::UnexpectedPath(p.within(segment)),
+            Self::UnexpectedIndex(p, e) => Self::UnexpectedIndex(p.within(segment), e),
+            Self::UnsupportedValue(p, v) => Self::UnsupportedValue(p.within(segment), v),
+            e => e,
+        }
+    }
+}
+
 pub trait Context {
     fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
     where
@@ -48,10 +100,82 @@ pub trait Context {
     fn get_from_context_at(&self, path: &str) -> Result<Value, Error> {
         self.get_from_context(path.split('.'))
     }
+
+    /// Looks up the value at the path like [`Context::get_from_context`], borrowing it from the context,
+    /// which operators use to compare it without cloning.
+    /// Returns the owned value by default, so contexts holding the values should override it to borrow them.
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.get_from_context(path).map(ValueRef::from)
+    }
+
+    fn get_ref_from_context_at(&self, path: &str) -> Result<ValueRef<'_>, Error> {
+        self.get_ref_from_context(path.split('.'))
+    }
+
+    /// Looks up the values at the paths like [`Context::get_ref_from_context`], returning them in the same order.
+    /// Looks up each path from the root by default. Contexts made of fields override it to descend into each field
+    /// once for all paths under it, so that the paths sharing a prefix share its lookup.
+    fn get_refs_from_context(&self, paths: &[&[&str]]) -> Vec<Result<ValueRef<'_>, Error>> {
+        paths
+            .iter()
+            .map(|p| self.get_ref_from_context(p.iter().copied()))
+            .collect()
+    }
+
+    /// Describes the shape of the context statically, used to validate rules against the context.
+    /// Returns [`Schema::Any`] by default, accepting any path and value.
+    fn schema() -> Schema {
+        Schema::Any
+    }
+}
+
+/// Looks up the paths like [`Context::get_refs_from_context`], descending into each field once for all paths under it.
+/// `field` looks up the paths relative to the field of the name, or returns `None` if there is no such field,
+/// in which case the paths are looked up from the context one by one to report the error.
+/// Used by the derived contexts.
+#[doc(hidden)]
+pub fn get_refs_from_fields<'c, C, F>(
+    context: &'c C,
+    paths: &[&[&str]],
+    field: F,
+) -> Vec<Result<ValueRef<'c>, Error>>
+where
+    C: Context + ?Sized,
+    F: Fn(&str, &[&[&str]]) -> Option<Vec<Result<ValueRef<'c>, Error>>>,
+{
+    let mut results = paths.iter().map(|_| None).collect::<Vec<_>>();
+    for (i, path) in paths.iter().enumerate() {
+        let name = match path.first() {
+            Some(n) if results[i].is_none() => *n,
+            _ => continue,
+        };
+
+        let (indices, rests) = paths
+            .iter()
+            .enumerate()
+            .skip(i)
+            .filter(|(_, p)| p.first() == Some(&name))
+            .map(|(j, p)| (j, &p[1..]))
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        if let Some(found) = field(name, &rests) {
+            for (j, result) in indices.into_iter().zip(found) {
+                results[j] = Some(result.map_err(|e| e.within(name)));
+            }
+        }
+    }
+
+    results
+        .into_iter()
+        .zip(paths)
+        .map(|(r, p)| r.unwrap_or_else(|| context.get_ref_from_context(p.iter().copied())))
+        .collect()
 }
 
 macro_rules! impl_context_primitive {
-    ($t: ty) => {
+    ($t: ty, $schema: ident) => {
         impl $crate::context::Context for $t {
             fn get_from_context<'a, I>(
                 &self,
@@ -67,26 +191,66 @@ macro_rules! impl_context_primitive {
                     Err($crate::context::Error::UnexpectedPath(path.into()))
                 }
             }
+
+            fn get_ref_from_context<'a, I>(
+                &self,
+                path: I,
+            ) -> Result<$crate::ValueRef<'_>, $crate::context::Error>
+            where
+                I: IntoIterator<Item = &'a str>,
+            {
+                let mut path = path.into_iter().peekable();
+                if path.peek().is_none() {
+                    Ok($crate::ValueRef::from(self))
+                } else {
+                    Err($crate::context::Error::UnexpectedPath(
+                        path.collect::<Vec<_>>().into(),
+                    ))
+                }
+            }
+
+            fn schema() -> $crate::schema::Schema {
+                $crate::schema::Schema::$schema
+            }
         }
     };
 }
 
-#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
-mod bits32 {
-    impl_context_primitive!(i32);
-    impl_context_primitive!(u32);
-}
+impl_context_primitive!(bool, Boolean);
+impl_context_primitive!(i8, Integer);
+impl_context_primitive!(i16, Integer);
+impl_context_primitive!(u8, Integer);
+impl_context_primitive!(u16, Integer);
+impl_context_primitive!(i32, Integer);
+impl_context_primitive!(u32, Integer);
+impl_context_primitive!(i64, Integer);
+impl_context_primitive!(u64, Integer);
+impl_context_primitive!(i128, Integer);
+impl_context_primitive!(isize, Integer);
+impl_context_primitive!(usize, Integer);
+impl_context_primitive!(char, String);
+impl_context_primitive!(String, String);
+impl_context_primitive!(str, String);
+impl_context_primitive!(IpAddr, IpAddr);
+impl_context_primitive!(Ipv4Addr, IpAddr);
+impl_context_primitive!(Ipv6Addr, IpAddr);
 
-#[cfg(target_pointer_width = "64")]
-mod bits64 {
-    impl_context_primitive!(i64);
-    impl_context_primitive!(u64);
-}
+impl Context for u128 {
+    /// Fails if the value exceeds the range of `i128`, which [`Value`] represents integers with.
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let value = Value::try_from(*self)
+            .map_err(|_| Error::UnsupportedValue([].into(), self.to_string()))?;
 
-impl_context_primitive!(isize);
-impl_context_primitive!(usize);
-impl_context_primitive!(String);
-impl_context_primitive!(&str);
+        value.get_from_context(path)
+    }
+
+    fn schema() -> Schema {
+        Schema::Integer
+    }
+}
 
 impl Context for Value {
     fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
@@ -94,20 +258,98 @@ impl Context for Value {
         I: IntoIterator<Item = &'a str>,
     {
         match self {
+            Self::Boolean(b) => b.get_from_context(path),
             Self::Integer(i) => i.get_from_context(path),
             Self::String(s) => s.get_from_context(path),
             Self::Array(a) => a.get_from_context(path),
+            Self::IpAddr(a) => a.get_from_context(path),
+            #[cfg(feature = "chrono")]
+            Self::Timestamp(t) => t.get_from_context(path),
+            #[cfg(feature = "chrono")]
+            Self::Duration(d) => d.get_from_context(path),
             Self::Optional(o) => o.as_deref().get_from_context(path),
         }
     }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        match self {
+            Self::Boolean(b) => b.get_ref_from_context(path),
+            Self::Integer(i) => i.get_ref_from_context(path),
+            Self::String(s) => s.get_ref_from_context(path),
+            Self::Array(a) => a.get_ref_from_context(path),
+            Self::IpAddr(a) => a.get_ref_from_context(path),
+            #[cfg(feature = "chrono")]
+            Self::Timestamp(t) => t.get_ref_from_context(path),
+            #[cfg(feature = "chrono")]
+            Self::Duration(d) => d.get_ref_from_context(path),
+            Self::Optional(Some(v)) => v.get_ref_from_context(path),
+            Self::Optional(None) => Ok(ValueRef::Missing),
+        }
+    }
 }
 
-impl Context for &Value {
+macro_rules! impl_context_deref {
+    ($($t: ty),*) => {
+        $(
+            impl<T> Context for $t
+            where
+                T: Context + ?Sized,
+            {
+                fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+                where
+                    I: IntoIterator<Item = &'a str>,
+                {
+                    (**self).get_from_context(path)
+                }
+
+                fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+                where
+                    I: IntoIterator<Item = &'a str>,
+                {
+                    (**self).get_ref_from_context(path)
+                }
+
+                fn get_refs_from_context(&self, paths: &[&[&str]]) -> Vec<Result<ValueRef<'_>, Error>> {
+                    (**self).get_refs_from_context(paths)
+                }
+
+                fn schema() -> Schema {
+                    T::schema()
+                }
+            }
+        )*
+    };
+}
+
+impl_context_deref!(&T, &mut T, Box<T>, Rc<T>, Arc<T>);
+
+impl<B> Context for Cow<'_, B>
+where
+    B: Context + ToOwned + ?Sized,
+{
     fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
     where
         I: IntoIterator<Item = &'a str>,
     {
-        (*self).get_from_context(path)
+        (**self).get_from_context(path)
+    }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        (**self).get_ref_from_context(path)
+    }
+
+    fn get_refs_from_context(&self, paths: &[&[&str]]) -> Vec<Result<ValueRef<'_>, Error>> {
+        (**self).get_refs_from_context(paths)
+    }
+
+    fn schema() -> Schema {
+        B::schema()
     }
 }
 
@@ -124,36 +366,344 @@ where
             _ => Ok(Value::Optional(None)),
         }
     }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        match self {
+            Some(ctx) => ctx.get_ref_from_context(path),
+            _ => Ok(ValueRef::Missing),
+        }
+    }
+
+    fn get_refs_from_context(&self, paths: &[&[&str]]) -> Vec<Result<ValueRef<'_>, Error>> {
+        match self {
+            Some(ctx) => ctx.get_refs_from_context(paths),
+            _ => paths.iter().map(|_| Ok(ValueRef::Missing)).collect(),
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::Optional(Box::new(T::schema()))
+    }
+}
+
+/// Looks up an element at the index in the head of the path.
+/// If the path is empty, returns all elements as an array.
+fn get_from_elements<'a, 'e, T, E, I>(elements: E, path: I) -> Result<Value, Error>
+where
+    T: Context + ?Sized + 'e,
+    E: IntoIterator<Item = &'e T>,
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut path = path.into_iter();
+    let (segment, index) = match path.next() {
+        Some(i) => (
+            i,
+            usize::from_str(i).map_err(|e| Error::UnexpectedIndex([i].into(), e))?,
+        ),
+        _ => {
+            return elements
+                .into_iter()
+                .enumerate()
+                .map(|(i, ctx)| {
+                    ctx.get_from_context([])
+                        .map_err(|e| e.within(&i.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array)
+        }
+    };
+
+    match elements.into_iter().nth(index) {
+        Some(ctx) => ctx.get_from_context(path).map_err(|e| e.within(segment)),
+        _ => Ok(Value::Optional(None)),
+    }
+}
+
+/// Looks up an element like [`get_from_elements`], borrowing the value.
+fn get_ref_from_elements<'a, 'e, T, E, I>(elements: E, path: I) -> Result<ValueRef<'e>, Error>
+where
+    T: Context + ?Sized + 'e,
+    E: IntoIterator<Item = &'e T>,
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut path = path.into_iter();
+    let (segment, index) = match path.next() {
+        Some(i) => (
+            i,
+            usize::from_str(i).map_err(|e| Error::UnexpectedIndex([i].into(), e))?,
+        ),
+        _ => {
+            return elements
+                .into_iter()
+                .enumerate()
+                .map(|(i, ctx)| {
+                    ctx.get_ref_from_context([])
+                        .map_err(|e| e.within(&i.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(ValueRef::Array)
+        }
+    };
+
+    match elements.into_iter().nth(index) {
+        Some(ctx) => ctx
+            .get_ref_from_context(path)
+            .map_err(|e| e.within(segment)),
+        _ => Ok(ValueRef::Missing),
+    }
 }
 
-impl<T> Context for Vec<T>
+macro_rules! impl_context_elements {
+    ($($t: ty),*) => {
+        $(
+            impl<T> Context for $t
+            where
+                T: Context,
+            {
+                fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+                where
+                    I: IntoIterator<Item = &'a str>,
+                {
+                    get_from_elements(self, path)
+                }
+
+                fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+                where
+                    I: IntoIterator<Item = &'a str>,
+                {
+                    get_ref_from_elements(self, path)
+                }
+
+                fn schema() -> Schema {
+                    Schema::Array(Box::new(T::schema()))
+                }
+            }
+        )*
+    };
+}
+
+impl_context_elements!([T], Vec<T>, VecDeque<T>, LinkedList<T>, BTreeSet<T>);
+
+impl<T, const N: usize> Context for [T; N]
 where
     T: Context,
-    for<'b> Value: From<&'b Vec<T>> + From<T>,
 {
     fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
     where
         I: IntoIterator<Item = &'a str>,
     {
-        let mut path = path.into_iter();
-        let index = match path.next() {
-            Some(i) => usize::from_str(i)?,
-            _ => return Ok(Value::from(self)),
-        };
+        get_from_elements(self, path)
+    }
 
-        match self.get(index) {
-            Some(ctx) => ctx.get_from_context(path),
-            _ => Ok(Value::Optional(None)),
-        }
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_ref_from_elements(self, path)
+    }
+
+    fn schema() -> Schema {
+        Schema::Array(Box::new(T::schema()))
     }
 }
 
+/// Elements are in the iteration order of the set, which is unspecified.
+impl<T, S> Context for HashSet<T, S>
+where
+    T: Context,
+{
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_from_elements(self, path)
+    }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_ref_from_elements(self, path)
+    }
+
+    fn schema() -> Schema {
+        Schema::Array(Box::new(T::schema()))
+    }
+}
+
+/// Looks up an entry by the key in the head of the path.
+/// Maps have no value representation, so the path must not be empty.
+/// An absent key is an absent attribute, as in the untyped documents.
+fn get_from_entries<'a, 'e, T, F, I>(get: F, path: I) -> Result<Value, Error>
+where
+    T: Context + 'e,
+    F: FnOnce(&str) -> Option<&'e T>,
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut path = path.into_iter();
+    let segment = match path.next() {
+        Some(s) => s,
+        _ => return Err(Error::UnsupportedValue([].into(), "map".to_string())),
+    };
+
+    match get(segment) {
+        Some(ctx) => ctx.get_from_context(path).map_err(|e| e.within(segment)),
+        _ => Err(Error::UnexpectedPath([segment].into())),
+    }
+}
+
+/// Looks up an entry like [`get_from_entries`], borrowing the value.
+fn get_ref_from_entries<'a, 'e, T, F, I>(get: F, path: I) -> Result<ValueRef<'e>, Error>
+where
+    T: Context + 'e,
+    F: FnOnce(&str) -> Option<&'e T>,
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut path = path.into_iter();
+    let segment = match path.next() {
+        Some(s) => s,
+        _ => return Err(Error::UnsupportedValue([].into(), "map".to_string())),
+    };
+
+    match get(segment) {
+        Some(ctx) => ctx
+            .get_ref_from_context(path)
+            .map_err(|e| e.within(segment)),
+        _ => Err(Error::UnexpectedPath([segment].into())),
+    }
+}
+
+impl<K, V, S> Context for HashMap<K, V, S>
+where
+    K: Borrow<str> + Eq + Hash,
+    V: Context,
+    S: BuildHasher,
+{
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_from_entries(|k| self.get(k), path)
+    }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_ref_from_entries(|k| self.get(k), path)
+    }
+
+    fn schema() -> Schema {
+        Schema::Map(Box::new(V::schema()))
+    }
+}
+
+impl<K, V> Context for BTreeMap<K, V>
+where
+    K: Borrow<str> + Ord,
+    V: Context,
+{
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_from_entries(|k| self.get(k), path)
+    }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_ref_from_entries(|k| self.get(k), path)
+    }
+
+    fn schema() -> Schema {
+        Schema::Map(Box::new(V::schema()))
+    }
+}
+
+macro_rules! impl_context_tuple {
+    ($(($($i: tt $t: ident),+)),*) => {
+        $(
+            impl<$($t),+> Context for ($($t,)+)
+            where
+                $($t: Context),+
+            {
+                fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+                where
+                    I: IntoIterator<Item = &'a str>,
+                {
+                    let mut path = path.into_iter();
+                    let segment = match path.next() {
+                        Some(s) => s,
+                        _ => {
+                            return Ok(Value::Array(vec![
+                                $(self.$i.get_from_context([]).map_err(|e| e.within(stringify!($i)))?),+
+                            ]))
+                        }
+                    };
+
+                    match segment {
+                        $(stringify!($i) => self.$i.get_from_context(path).map_err(|e| e.within(segment)),)+
+                        _ => match usize::from_str(segment) {
+                            Ok(_) => Ok(Value::Optional(None)),
+                            Err(e) => Err(Error::UnexpectedIndex([segment].into(), e)),
+                        },
+                    }
+                }
+
+                fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+                where
+                    I: IntoIterator<Item = &'a str>,
+                {
+                    let mut path = path.into_iter();
+                    let segment = match path.next() {
+                        Some(s) => s,
+                        _ => {
+                            return Ok(ValueRef::Array(vec![
+                                $(self.$i.get_ref_from_context([]).map_err(|e| e.within(stringify!($i)))?),+
+                            ]))
+                        }
+                    };
+
+                    match segment {
+                        $(stringify!($i) => self.$i.get_ref_from_context(path).map_err(|e| e.within(segment)),)+
+                        _ => match usize::from_str(segment) {
+                            Ok(_) => Ok(ValueRef::Missing),
+                            Err(e) => Err(Error::UnexpectedIndex([segment].into(), e)),
+                        },
+                    }
+                }
+
+                fn schema() -> Schema {
+                    Schema::Tuple(vec![$($t::schema()),+])
+                }
+            }
+        )*
+    };
+}
+
+impl_context_tuple!(
+    (0 A),
+    (0 A, 1 B),
+    (0 A, 1 B, 2 C),
+    (0 A, 1 B, 2 C, 3 D),
+    (0 A, 1 B, 2 C, 3 D, 4 E),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H)
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn context_primitives() {
+        assert_eq!(Value::from(true), true.get_from_context([]).unwrap());
         assert_eq!(Value::from(123_i32), 123_i32.get_from_context([]).unwrap());
         assert_eq!(Value::from(123_u32), 123_u32.get_from_context([]).unwrap());
         assert_eq!(Value::from(123_i64), 123_i64.get_from_context([]).unwrap());
@@ -166,6 +716,21 @@ mod tests {
             Value::from(123_usize),
             123_usize.get_from_context([]).unwrap(),
         );
+        assert_eq!(Value::from(123_i8), 123_i8.get_from_context([]).unwrap());
+        assert_eq!(
+            Value::from(u64::MAX),
+            u64::MAX.get_from_context([]).unwrap(),
+        );
+        assert_eq!(
+            Value::from(i128::MAX),
+            (i128::MAX as u128).get_from_context([]).unwrap(),
+        );
+        assert!(matches!(
+            u128::MAX.get_from_context([]).unwrap_err(),
+            Error::UnsupportedValue(..),
+        ));
+        assert_eq!(Value::from(123_u16), 123_u16.get_from_context([]).unwrap());
+        assert_eq!(Value::from("a"), 'a'.get_from_context([]).unwrap());
         assert_eq!(Value::from("abc"), "abc".get_from_context([]).unwrap());
         assert_eq!(
             Value::from("abc"),
@@ -203,7 +768,157 @@ mod tests {
 
         assert!(matches!(
             vec!["abc", "def"].get_from_context(["abc"]).unwrap_err(),
-            Error::UnexpectedIndex(_),
+            Error::UnexpectedIndex(..),
+        ));
+    }
+
+    #[test]
+    fn error_path() {
+        assert_eq!(
+            "Unexpected path: 1.abc.def",
+            vec!["abc", "def"]
+                .get_from_context(["1", "abc", "def"])
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unexpected index in an array: 0.abc (invalid digit found in string)",
+            vec![vec!["abc"]]
+                .get_from_context(["0", "abc"])
+                .unwrap_err()
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn context_pointers() {
+        assert_eq!(
+            Value::from("abc"),
+            Box::new("abc").get_from_context([]).unwrap(),
+        );
+        assert_eq!(Value::from(123), Rc::new(123).get_from_context([]).unwrap());
+        assert_eq!(
+            Value::from(123),
+            Arc::new(123).get_from_context([]).unwrap()
+        );
+        assert_eq!(Value::from(123), (&&123).get_from_context([]).unwrap());
+        assert_eq!(
+            Value::from("abc"),
+            Cow::<str>::Borrowed("abc").get_from_context([]).unwrap(),
+        );
+        assert_eq!(
+            Value::from("abc"),
+            Box::<str>::from("abc").get_from_context([]).unwrap(),
+        );
+    }
+
+    #[test]
+    fn context_collections() {
+        assert_eq!(
+            Value::from(vec![123, 456]),
+            [123, 456].get_from_context([]).unwrap(),
+        );
+        assert_eq!(
+            Value::from(456),
+            [123, 456][..].get_from_context(["1"]).unwrap(),
+        );
+        assert_eq!(
+            Value::from(456),
+            VecDeque::from([123, 456]).get_from_context(["1"]).unwrap(),
+        );
+        assert_eq!(
+            Value::from(vec![123, 456]),
+            BTreeSet::from([456, 123]).get_from_context([]).unwrap(),
+        );
+        assert_eq!(
+            Value::from(vec![123]),
+            HashSet::from([123]).get_from_context([]).unwrap(),
+        );
+        assert_eq!(
+            Value::from(vec![vec![123], vec![456]]),
+            vec![vec![123], vec![456]].get_from_context([]).unwrap(),
+        );
+    }
+
+    #[test]
+    fn context_maps() {
+        let map = HashMap::from([("abc".to_string(), 123)]);
+
+        assert_eq!(Value::from(123), map.get_from_context(["abc"]).unwrap());
+        assert!(matches!(
+            map.get_from_context(["def"]).unwrap_err(),
+            Error::UnexpectedPath(..),
+        ));
+        assert!(matches!(
+            map.get_ref_from_context(["def", "0"]).unwrap_err(),
+            Error::UnexpectedPath(..),
+        ));
+        assert!(matches!(
+            map.get_from_context([]).unwrap_err(),
+            Error::UnsupportedValue(..),
+        ));
+
+        let map = BTreeMap::from([("abc", vec![123])]);
+
+        assert_eq!(
+            Value::from(123),
+            map.get_from_context(["abc", "0"]).unwrap(),
+        );
+    }
+
+    #[test]
+    fn context_borrowed() {
+        let strings = vec!["abc".to_string(), "def".to_string()];
+
+        assert!(matches!(
+            strings.get_ref_from_context(["1"]).unwrap(),
+            ValueRef::String(Cow::Borrowed("def")),
+        ));
+        assert_eq!(
+            strings.get_from_context([]).unwrap(),
+            Value::from(strings.get_ref_from_context([]).unwrap()),
+        );
+        assert!(matches!(
+            Some("abc").get_ref_from_context([]).unwrap(),
+            ValueRef::String(Cow::Borrowed("abc")),
+        ));
+        assert!(None::<String>
+            .get_ref_from_context(["abc"])
+            .unwrap()
+            .is_missing());
+
+        let map = BTreeMap::from([("abc", (123, "def"))]);
+
+        assert!(matches!(
+            map.get_ref_from_context(["abc", "1"]).unwrap(),
+            ValueRef::String(Cow::Borrowed("def")),
+        ));
+        assert!(matches!(
+            map.get_ref_from_context(["abc", "abc"]).unwrap_err(),
+            Error::UnexpectedIndex(..),
+        ));
+    }
+
+    #[test]
+    fn context_tuples() {
+        let tuple = (123, "abc", Some(true));
+
+        assert_eq!(
+            Value::from(vec![
+                Value::from(123),
+                Value::from("abc"),
+                Value::from(Some(true))
+            ]),
+            tuple.get_from_context([]).unwrap(),
+        );
+        assert_eq!(Value::from("abc"), tuple.get_from_context(["1"]).unwrap());
+        assert_eq!(
+            Value::Optional(None),
+            tuple.get_from_context(["3"]).unwrap(),
+        );
+        assert!(matches!(
+            tuple.get_from_context(["abc"]).unwrap_err(),
+            Error::UnexpectedIndex(..),
         ));
     }
 }
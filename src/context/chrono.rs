@@ -0,0 +1,103 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use crate::context::{Context, Error};
+use crate::schema::Schema;
+use crate::value::Value;
+
+/// Ensures the path is empty, as temporal values have no children.
+fn leaf<'a, I>(path: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let path = path.into_iter().collect::<Vec<_>>();
+    match path.is_empty() {
+        true => Ok(()),
+        false => Err(Error::UnexpectedPath(path.into())),
+    }
+}
+
+impl<Tz> Context for DateTime<Tz>
+where
+    Tz: TimeZone,
+{
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        leaf(path).map(|_| Value::from(self.clone()))
+    }
+
+    fn schema() -> Schema {
+        Schema::Timestamp
+    }
+}
+
+impl Context for SystemTime {
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        leaf(path).map(|_| Value::from(DateTime::<Utc>::from(*self)))
+    }
+
+    fn schema() -> Schema {
+        Schema::Timestamp
+    }
+}
+
+impl Context for Duration {
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        leaf(path).map(|_| Value::from(*self))
+    }
+
+    fn schema() -> Schema {
+        Schema::Duration
+    }
+}
+
+impl Context for std::time::Duration {
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        leaf(path)?;
+        Duration::from_std(*self)
+            .map(Value::from)
+            .map_err(|_| Error::UnsupportedValue([].into(), format!("{:?}", self)))
+    }
+
+    fn schema() -> Schema {
+        Schema::Duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_chrono() {
+        let t = DateTime::parse_from_rfc3339("2024-01-01T09:00:00+09:00").unwrap();
+        let context = vec![t];
+
+        assert_eq!(
+            Value::from(DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap()),
+            context.get_from_context_at("0").unwrap(),
+        );
+        assert!(matches!(
+            context.get_from_context_at("0.foo").unwrap_err(),
+            Error::UnexpectedPath(p) if p.to_string() == "0.foo",
+        ));
+        assert_eq!(
+            Value::from(Duration::seconds(90)),
+            std::time::Duration::from_secs(90)
+                .get_from_context([])
+                .unwrap(),
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Path walking shared by the untyped documents, such as JSON, YAML and TOML values.
+//!
+//! Maps are walked by their keys and arrays by their indices. Null is a missing value, with every path under it missing,
+//! while an absent key is an absent attribute. Floats are unsupported, as [`Value`](crate::value::Value) has no
+//! floating point numbers, so looking them up fails with [`Error::UnsupportedValue`].
+
+use std::str::FromStr;
+
+use crate::context::Error;
+use crate::value::ValueRef;
+
+/// Untyped document whose nodes are walked in the same way regardless of the format.
+pub(crate) trait Document: Sized {
+    /// Maps in the format.
+    type Map;
+
+    /// Name of the maps in the format, reported when a map is looked up as a value.
+    const MAP: &'static str;
+
+    fn node(&self) -> Node<'_, Self>;
+
+    /// Looks up the entry at the key in the map.
+    fn entry<'d>(map: &'d Self::Map, key: &str) -> Option<&'d Self>;
+}
+
+/// Node of a document, classified for walking the path.
+pub(crate) enum Node<'d, D>
+where
+    D: Document,
+{
+    /// Null, which TOML does not have.
+    #[cfg_attr(not(any(feature = "json", feature = "yaml")), allow(dead_code))]
+    Null,
+    Scalar(ValueRef<'d>),
+
+    /// Scalar that cannot be represented as a value, e.g. a float.
+    Unsupported(String),
+    Array(&'d [D]),
+    Map(&'d D::Map),
+}
+
+/// Looks up the value at the path in the document, borrowing it.
+pub(crate) fn get_ref<'d, 'a, D, I>(document: &'d D, path: I) -> Result<ValueRef<'d>, Error>
+where
+    D: Document,
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut path = path.into_iter();
+    let segment = match path.next() {
+        Some(s) => s,
+        _ => return to_value(document),
+    };
+
+    let child = match document.node() {
+        Node::Null => return Ok(ValueRef::Missing),
+        Node::Map(map) => match D::entry(map, segment) {
+            Some(d) => Some(d),
+            // An absent key is an absent attribute, unlike a null value.
+            _ => return Err(Error::UnexpectedPath([segment].into())),
+        },
+        Node::Array(array) => {
+            let index = usize::from_str(segment)
+                .map_err(|e| Error::UnexpectedIndex([segment].into(), e))?;
+            array.get(index)
+        }
+        _ => return Err(Error::UnexpectedPath([segment].into())),
+    };
+
+    match child {
+        Some(d) => get_ref(d, path).map_err(|e| e.within(segment)),
+        _ => Ok(ValueRef::Missing),
+    }
+}
+
+fn to_value<D>(document: &D) -> Result<ValueRef<'_>, Error>
+where
+    D: Document,
+{
+    Ok(match document.node() {
+        Node::Null => ValueRef::Missing,
+        Node::Scalar(v) => v,
+        Node::Unsupported(v) => return Err(Error::UnsupportedValue([].into(), v)),
+        Node::Array(array) => ValueRef::Array(
+            array
+                .iter()
+                .enumerate()
+                .map(|(i, d)| to_value(d).map_err(|e| e.within(&i.to_string())))
+                .collect::<Result<_, _>>()?,
+        ),
+        Node::Map(_) => return Err(Error::UnsupportedValue([].into(), D::MAP.to_string())),
+    })
+}
@@ -0,0 +1,124 @@
+use serde_json::{Map, Value as Json};
+
+use crate::context::document::{get_ref, Document, Node};
+use crate::context::{Context, Error};
+use crate::value::{Value, ValueRef};
+
+impl Context for Json {
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.get_ref_from_context(path).map(Value::from)
+    }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_ref(self, path)
+    }
+}
+
+impl Document for Json {
+    type Map = Map<String, Json>;
+
+    const MAP: &'static str = "object";
+
+    fn node(&self) -> Node<'_, Self> {
+        match self {
+            Self::Null => Node::Null,
+            Self::Bool(b) => Node::Scalar(ValueRef::Boolean(*b)),
+            Self::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Node::Scalar(ValueRef::from(&i)),
+                (_, Some(u)) => Node::Scalar(ValueRef::from(&u)),
+                _ => Node::Unsupported(n.to_string()),
+            },
+            Self::String(s) => Node::Scalar(ValueRef::from(s)),
+            Self::Array(a) => Node::Array(a),
+            Self::Object(map) => Node::Map(map),
+        }
+    }
+
+    fn entry<'d>(map: &'d Self::Map, key: &str) -> Option<&'d Self> {
+        map.get(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn context_json() {
+        let context = json!({
+            "foo": 123,
+            "bar": {
+                "baz": "abc",
+                "array": [true, null],
+            },
+            "float": 1.5,
+        });
+
+        assert_eq!(
+            Value::from(123),
+            context.get_from_context_at("foo").unwrap()
+        );
+        assert_eq!(
+            Value::from("abc"),
+            context.get_from_context_at("bar.baz").unwrap(),
+        );
+        assert_eq!(
+            Value::from(vec![Value::from(true), Value::Optional(None)]),
+            context.get_from_context_at("bar.array").unwrap(),
+        );
+        assert_eq!(
+            Value::from(true),
+            context.get_from_context_at("bar.array.0").unwrap(),
+        );
+        assert_eq!(
+            Value::Optional(None),
+            context.get_from_context_at("bar.array.1.qux").unwrap(),
+        );
+        assert_eq!(
+            "Unexpected path: bar.qux",
+            context
+                .get_from_context_at("bar.qux")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unexpected path: foo.qux",
+            context
+                .get_from_context_at("foo.qux")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unsupported value: float (1.5)",
+            context
+                .get_from_context_at("float")
+                .unwrap_err()
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn context_json_agrees_with_map() {
+        let json = json!({ "foo": { "bar": 123 } });
+        let map = HashMap::from([("foo", HashMap::from([("bar", 123)]))]);
+
+        for path in ["foo.bar", "foo.qux", "qux", "qux.bar", "foo.bar.qux"] {
+            assert_eq!(
+                map.get_from_context_at(path).map_err(|e| e.to_string()),
+                json.get_from_context_at(path).map_err(|e| e.to_string()),
+                "{}",
+                path,
+            );
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use semver::Version;
+
+use crate::context::{Context, Error};
+use crate::schema::Schema;
+use crate::value::Value;
+
+impl Context for Version {
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let path = path.into_iter().collect::<Vec<_>>();
+        if path.is_empty() {
+            Ok(Value::from(self.to_string()))
+        } else {
+            Err(Error::UnexpectedPath(path.into()))
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::String
+    }
+}
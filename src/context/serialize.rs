@@ -0,0 +1,855 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use serde::ser::{
+    self, Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::Serialize;
+
+use crate::context::{Context, Error};
+use crate::value::Value;
+
+/// Adapter to use any serializable type as a context, without deriving [`Context`].
+///
+/// The value is walked by a serializer that descends only into the requested path,
+/// so that the other fields are never serialized.
+/// Structs are addressed by their field names, maps by their keys and sequences by their indices.
+/// Newtypes, options and enum variants are transparent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializeContext<T>(pub T);
+
+impl<T> Context for SerializeContext<T>
+where
+    T: Serialize,
+{
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let path = path.into_iter().collect::<Vec<_>>();
+
+        self.0
+            .serialize(PathSerializer { path: &path })
+            .map_err(|e| e.0)
+    }
+}
+
+struct SerializeError(Error);
+
+impl Debug for SerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for SerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+impl ser::Error for SerializeError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(Error::Other(msg.to_string().into()))
+    }
+}
+
+impl From<Error> for SerializeError {
+    fn from(value: Error) -> Self {
+        Self(value)
+    }
+}
+
+/// Serializer that descends into the value at the path.
+struct PathSerializer<'p> {
+    path: &'p [&'p str],
+}
+
+impl<'p> PathSerializer<'p> {
+    /// Returns the value if the path is consumed, otherwise fails as the value cannot be descended.
+    fn scalar(self, value: Value) -> Result<Value, SerializeError> {
+        match self.path.first() {
+            Some(s) => Err(Error::UnexpectedPath([*s].into()).into()),
+            _ => Ok(value),
+        }
+    }
+
+    fn unsupported(self, kind: &str) -> Result<Value, SerializeError> {
+        match self.path.first() {
+            Some(s) => Err(Error::UnexpectedPath([*s].into()).into()),
+            _ => Err(Error::UnsupportedValue([].into(), kind.to_string()).into()),
+        }
+    }
+
+    fn sequence(self) -> Result<Compound<'p>, SerializeError> {
+        let (segment, rest) = match self.path.split_first() {
+            Some(s) => s,
+            _ => return Ok(Compound::Collect(vec![])),
+        };
+
+        let index =
+            usize::from_str(segment).map_err(|e| Error::UnexpectedIndex([*segment].into(), e))?;
+
+        Ok(Compound::Element {
+            segment,
+            index,
+            current: 0,
+            rest,
+            found: None,
+        })
+    }
+
+    fn structure(self, kind: &str) -> Result<Compound<'p>, SerializeError> {
+        match self.path.split_first() {
+            Some((segment, rest)) => Ok(Compound::Field {
+                segment,
+                rest,
+                found: None,
+            }),
+            _ => Err(Error::UnsupportedValue([].into(), kind.to_string()).into()),
+        }
+    }
+}
+
+impl<'p> ser::Serializer for PathSerializer<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+    type SerializeSeq = Compound<'p>;
+    type SerializeTuple = Compound<'p>;
+    type SerializeTupleStruct = Compound<'p>;
+    type SerializeTupleVariant = Compound<'p>;
+    type SerializeMap = Compound<'p>;
+    type SerializeStruct = Compound<'p>;
+    type SerializeStructVariant = Compound<'p>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, SerializeError> {
+        match Value::try_from(v) {
+            Ok(value) => self.scalar(value),
+            _ => self.unsupported(&v.to_string()),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, SerializeError> {
+        self.unsupported(&v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, SerializeError> {
+        self.unsupported(&v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, SerializeError> {
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            seq.element(byte)?;
+        }
+        seq.finish()
+    }
+
+    fn serialize_none(self) -> Result<Value, SerializeError> {
+        Ok(Value::Optional(None))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, SerializeError> {
+        self.scalar(Value::Optional(None))
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Value, SerializeError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Value, SerializeError> {
+        self.scalar(Value::from(variant))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Value, SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Value, SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Compound<'p>, SerializeError> {
+        self.sequence()
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Compound<'p>, SerializeError> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'p>, SerializeError> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'p>, SerializeError> {
+        self.sequence()
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Compound<'p>, SerializeError> {
+        match self.path.split_first() {
+            Some((segment, rest)) => Ok(Compound::Entry {
+                segment,
+                rest,
+                matched: false,
+                found: None,
+            }),
+            _ => Err(Error::UnsupportedValue([].into(), "map".to_string()).into()),
+        }
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Compound<'p>, SerializeError> {
+        self.structure("struct")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Compound<'p>, SerializeError> {
+        self.structure("struct variant")
+    }
+}
+
+/// State of serializing a compound value.
+enum Compound<'p> {
+    /// Collects all elements of a sequence at the end of the path.
+    Collect(Vec<Value>),
+
+    /// Looks for an element of a sequence at the index.
+    Element {
+        segment: &'p str,
+        index: usize,
+        current: usize,
+        rest: &'p [&'p str],
+        found: Option<Value>,
+    },
+
+    /// Looks for a field of a struct with the name.
+    Field {
+        segment: &'p str,
+        rest: &'p [&'p str],
+        found: Option<Value>,
+    },
+
+    /// Looks for an entry of a map with the key.
+    Entry {
+        segment: &'p str,
+        rest: &'p [&'p str],
+        matched: bool,
+        found: Option<Value>,
+    },
+}
+
+impl<'p> Compound<'p> {
+    fn element<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        match self {
+            Self::Collect(values) => values.push(value.serialize(PathSerializer { path: &[] })?),
+            Self::Element {
+                segment,
+                index,
+                current,
+                rest,
+                found,
+            } => {
+                if found.is_none() && index == current {
+                    *found = Some(descend(value, segment, rest)?);
+                }
+                *current += 1;
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    fn field<T>(&mut self, key: &str, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        match self {
+            Self::Field {
+                segment,
+                rest,
+                found,
+            } => {
+                if found.is_none() && key == *segment {
+                    *found = Some(descend(value, segment, rest)?);
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, SerializeError> {
+        match self {
+            Self::Collect(values) => Ok(Value::Array(values)),
+            Self::Element { found, .. } => Ok(found.unwrap_or(Value::Optional(None))),
+            Self::Field { segment, found, .. } | Self::Entry { segment, found, .. } => {
+                found.ok_or_else(|| Error::UnexpectedPath([segment].into()).into())
+            }
+        }
+    }
+}
+
+fn descend<T>(value: &T, segment: &str, rest: &[&str]) -> Result<Value, SerializeError>
+where
+    T: ?Sized + Serialize,
+{
+    value
+        .serialize(PathSerializer { path: rest })
+        .map_err(|e| e.0.within(segment).into())
+}
+
+impl<'p> SerializeSeq for Compound<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Value, SerializeError> {
+        self.finish()
+    }
+}
+
+impl<'p> SerializeTuple for Compound<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Value, SerializeError> {
+        self.finish()
+    }
+}
+
+impl<'p> SerializeTupleStruct for Compound<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Value, SerializeError> {
+        self.finish()
+    }
+}
+
+impl<'p> SerializeTupleVariant for Compound<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Value, SerializeError> {
+        self.finish()
+    }
+}
+
+impl<'p> SerializeStruct for Compound<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Value, SerializeError> {
+        self.finish()
+    }
+}
+
+impl<'p> SerializeStructVariant for Compound<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<Value, SerializeError> {
+        self.finish()
+    }
+}
+
+impl<'p> SerializeMap for Compound<'p> {
+    type Ok = Value;
+    type Error = SerializeError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        if let Self::Entry {
+            segment,
+            matched,
+            found,
+            ..
+        } = self
+        {
+            *matched =
+                found.is_none() && key.serialize(KeySerializer)?.as_deref() == Some(*segment);
+        }
+
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        if let Self::Entry {
+            segment,
+            rest,
+            matched: true,
+            found,
+        } = self
+        {
+            *found = Some(descend(value, segment, rest)?);
+        }
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, SerializeError> {
+        self.finish()
+    }
+}
+
+/// Serializer that renders a key of a map as a string, if it is a scalar.
+struct KeySerializer;
+
+impl KeySerializer {
+    fn render<T>(value: T) -> Result<Option<String>, SerializeError>
+    where
+        T: ToString,
+    {
+        Ok(Some(value.to_string()))
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = Option<String>;
+    type Error = SerializeError;
+    type SerializeSeq = Impossible<Option<String>, SerializeError>;
+    type SerializeTuple = Impossible<Option<String>, SerializeError>;
+    type SerializeTupleStruct = Impossible<Option<String>, SerializeError>;
+    type SerializeTupleVariant = Impossible<Option<String>, SerializeError>;
+    type SerializeMap = Impossible<Option<String>, SerializeError>;
+    type SerializeStruct = Impossible<Option<String>, SerializeError>;
+    type SerializeStructVariant = Impossible<Option<String>, SerializeError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<Option<String>, SerializeError> {
+        Ok(None)
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<Option<String>, SerializeError> {
+        Ok(None)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Option<String>, SerializeError> {
+        Self::render(v)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Option<String>, SerializeError> {
+        Ok(None)
+    }
+
+    fn serialize_none(self) -> Result<Option<String>, SerializeError> {
+        Ok(None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Option<String>, SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Option<String>, SerializeError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Option<String>, SerializeError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Option<String>, SerializeError> {
+        Self::render(variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Option<String>, SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Option<String>, SerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(None)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, SerializeError> {
+        Err(ser::Error::custom("sequences cannot be a key of a map"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, SerializeError> {
+        Err(ser::Error::custom("tuples cannot be a key of a map"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerializeError> {
+        Err(ser::Error::custom("tuple structs cannot be a key of a map"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerializeError> {
+        Err(ser::Error::custom(
+            "tuple variants cannot be a key of a map",
+        ))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, SerializeError> {
+        Err(ser::Error::custom("maps cannot be a key of a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, SerializeError> {
+        Err(ser::Error::custom("structs cannot be a key of a map"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, SerializeError> {
+        Err(ser::Error::custom(
+            "struct variants cannot be a key of a map",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Nested {
+        baz: String,
+        array: Vec<u32>,
+        map: BTreeMap<String, i32>,
+    }
+
+    #[derive(Serialize)]
+    enum Kind {
+        Foo,
+        Bar { qux: u8 },
+    }
+
+    #[derive(Serialize)]
+    struct Foreign {
+        foo: usize,
+        bar: Option<Nested>,
+        kind: Kind,
+        other: Kind,
+        float: f64,
+    }
+
+    #[test]
+    fn context_serialize() {
+        let context = SerializeContext(Foreign {
+            foo: 123,
+            bar: Some(Nested {
+                baz: "abc".to_string(),
+                array: vec![456, 789],
+                map: BTreeMap::from([("def".to_string(), 1)]),
+            }),
+            kind: Kind::Foo,
+            other: Kind::Bar { qux: 2 },
+            float: 1.5,
+        });
+
+        assert_eq!(
+            Value::from(123),
+            context.get_from_context_at("foo").unwrap()
+        );
+        assert_eq!(
+            Value::from("abc"),
+            context.get_from_context_at("bar.baz").unwrap(),
+        );
+        assert_eq!(
+            Value::from(vec![456, 789]),
+            context.get_from_context_at("bar.array").unwrap(),
+        );
+        assert_eq!(
+            Value::from(789),
+            context.get_from_context_at("bar.array.1").unwrap(),
+        );
+        assert_eq!(
+            Value::Optional(None),
+            context.get_from_context_at("bar.array.2").unwrap(),
+        );
+        assert_eq!(
+            Value::from(1),
+            context.get_from_context_at("bar.map.def").unwrap(),
+        );
+        assert_eq!(
+            "Unexpected path: bar.map.ghi",
+            context
+                .get_from_context_at("bar.map.ghi")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            Value::from("Foo"),
+            context.get_from_context_at("kind").unwrap(),
+        );
+        assert_eq!(
+            Value::from(2),
+            context.get_from_context_at("other.qux").unwrap(),
+        );
+
+        // The float is not serialized unless it is requested.
+        assert_eq!(
+            "Unsupported value: float (1.5)",
+            context
+                .get_from_context_at("float")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unexpected path: bar.bazz",
+            context
+                .get_from_context_at("bar.bazz")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unexpected path: foo.bar",
+            context
+                .get_from_context_at("foo.bar")
+                .unwrap_err()
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn context_serialize_none() {
+        let context = SerializeContext(Foreign {
+            foo: 123,
+            bar: None,
+            kind: Kind::Foo,
+            other: Kind::Foo,
+            float: 0.0,
+        });
+
+        assert_eq!(
+            Value::Optional(None),
+            context.get_from_context_at("bar.baz").unwrap(),
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::borrow::Cow;
+
+use toml::{Table, Value as Toml};
+
+use crate::context::document::{get_ref, Document, Node};
+use crate::context::{Context, Error};
+use crate::value::{Value, ValueRef};
+
+impl Context for Toml {
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.get_ref_from_context(path).map(Value::from)
+    }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_ref(self, path)
+    }
+}
+
+impl Document for Toml {
+    type Map = Table;
+
+    const MAP: &'static str = "table";
+
+    fn node(&self) -> Node<'_, Self> {
+        match self {
+            Self::Boolean(b) => Node::Scalar(ValueRef::Boolean(*b)),
+            Self::Integer(i) => Node::Scalar(ValueRef::from(i)),
+            Self::Float(f) => Node::Unsupported(f.to_string()),
+            Self::String(s) => Node::Scalar(ValueRef::from(s)),
+            Self::Datetime(d) => Node::Scalar(ValueRef::String(Cow::Owned(d.to_string()))),
+            Self::Array(a) => Node::Array(a),
+            Self::Table(table) => Node::Map(table),
+        }
+    }
+
+    fn entry<'d>(map: &'d Self::Map, key: &str) -> Option<&'d Self> {
+        map.get(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_toml() {
+        // language=toml
+        let toml = r#"
+        foo = 123
+        float = 1.5
+
+        [bar]
+        baz = "abc"
+        array = [true, false]
+        "#;
+
+        let context = toml::from_str::<Toml>(toml).unwrap();
+
+        assert_eq!(
+            Value::from(123),
+            context.get_from_context_at("foo").unwrap()
+        );
+        assert_eq!(
+            Value::from("abc"),
+            context.get_from_context_at("bar.baz").unwrap(),
+        );
+        assert_eq!(
+            Value::from(vec![true, false]),
+            context.get_from_context_at("bar.array").unwrap(),
+        );
+        assert_eq!(
+            Value::from(true),
+            context.get_from_context_at("bar.array.0").unwrap(),
+        );
+        assert_eq!(
+            "Unexpected path: bar.array.1.qux",
+            context
+                .get_from_context_at("bar.array.1.qux")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unexpected path: bar.qux",
+            context
+                .get_from_context_at("bar.qux")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unexpected path: foo.qux",
+            context
+                .get_from_context_at("foo.qux")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unsupported value: float (1.5)",
+            context
+                .get_from_context_at("float")
+                .unwrap_err()
+                .to_string(),
+        );
+    }
+}
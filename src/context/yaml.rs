@@ -0,0 +1,161 @@
+use serde_yaml::{Mapping, Value as Yaml};
+
+use crate::context::document::{get_ref, Document, Node};
+use crate::context::{Context, Error};
+use crate::value::{Value, ValueRef};
+
+impl Context for Yaml {
+    fn get_from_context<'a, I>(&self, path: I) -> Result<Value, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.get_ref_from_context(path).map(Value::from)
+    }
+
+    fn get_ref_from_context<'a, I>(&self, path: I) -> Result<ValueRef<'_>, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        get_ref(self, path)
+    }
+}
+
+impl Document for Yaml {
+    type Map = Mapping;
+
+    const MAP: &'static str = "mapping";
+
+    fn node(&self) -> Node<'_, Self> {
+        match self {
+            Self::Null => Node::Null,
+            Self::Bool(b) => Node::Scalar(ValueRef::Boolean(*b)),
+            Self::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Node::Scalar(ValueRef::from(&i)),
+                (_, Some(u)) => Node::Scalar(ValueRef::from(&u)),
+                _ => Node::Unsupported(n.to_string()),
+            },
+            Self::String(s) => Node::Scalar(ValueRef::from(s)),
+            Self::Sequence(a) => Node::Array(a),
+            Self::Mapping(map) => Node::Map(map),
+            Self::Tagged(tagged) => tagged.value.node(),
+        }
+    }
+
+    /// Looks up the entry at the key in the map.
+    /// Keys that are not strings, such as numbers and booleans, are matched by their string forms.
+    fn entry<'d>(map: &'d Self::Map, key: &str) -> Option<&'d Self> {
+        map.get(key).or_else(|| {
+            map.iter()
+                .find(|(k, _)| match k {
+                    Self::Number(n) => n.to_string() == key,
+                    Self::Bool(b) => b.to_string() == key,
+                    _ => false,
+                })
+                .map(|(_, v)| v)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_yaml() {
+        // language=yaml
+        let yaml = r#"
+        foo: 123
+        bar:
+          baz: abc
+          array:
+            - true
+            - ~
+        float: 1.5
+        "#;
+
+        let context = serde_yaml::from_str::<Yaml>(yaml).unwrap();
+
+        assert_eq!(
+            Value::from(123),
+            context.get_from_context_at("foo").unwrap()
+        );
+        assert_eq!(
+            Value::from("abc"),
+            context.get_from_context_at("bar.baz").unwrap(),
+        );
+        assert_eq!(
+            Value::from(vec![Value::from(true), Value::Optional(None)]),
+            context.get_from_context_at("bar.array").unwrap(),
+        );
+        assert_eq!(
+            Value::from(true),
+            context.get_from_context_at("bar.array.0").unwrap(),
+        );
+        assert_eq!(
+            Value::Optional(None),
+            context.get_from_context_at("bar.array.1.qux").unwrap(),
+        );
+        assert_eq!(
+            "Unexpected path: bar.qux",
+            context
+                .get_from_context_at("bar.qux")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unexpected path: foo.qux",
+            context
+                .get_from_context_at("foo.qux")
+                .unwrap_err()
+                .to_string(),
+        );
+        assert_eq!(
+            "Unsupported value: float (1.5)",
+            context
+                .get_from_context_at("float")
+                .unwrap_err()
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn context_yaml_keys() {
+        // language=yaml
+        let yaml = r#"
+        codes:
+          404: not found
+          0x1f4: internal server error
+        flags:
+          true: enabled
+        floats:
+          - 1
+          - 2.5
+        "#;
+
+        let context = serde_yaml::from_str::<Yaml>(yaml).unwrap();
+
+        assert_eq!(
+            Value::from("not found"),
+            context.get_from_context_at("codes.404").unwrap(),
+        );
+        assert_eq!(
+            Value::from("internal server error"),
+            context.get_from_context_at("codes.500").unwrap(),
+        );
+        assert_eq!(
+            Value::from("enabled"),
+            context.get_from_context_at("flags.true").unwrap(),
+        );
+        assert_eq!(
+            Value::from(1),
+            context.get_from_context_at("floats.0").unwrap(),
+        );
+        assert_eq!(
+            "Unsupported value: floats.1 (2.5)",
+            context
+                .get_from_context_at("floats")
+                .unwrap_err()
+                .to_string(),
+        );
+    }
+}
@@ -0,0 +1,552 @@
+//! Coverage of rules by a corpus of contexts, to find the conditions that are never exercised by tests.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Write};
+
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::Location;
+use crate::rule::{Rule, Rules};
+
+/// Coverage of an operator within a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Condition {
+    /// Whether the operator is within the target of the rule instead of its operator.
+    pub target: bool,
+    pub location: Location,
+    pub operator: &'static str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#where: Option<String>,
+
+    /// Number of contexts the operator was evaluated against, without being short-circuited.
+    pub evaluated: usize,
+
+    /// Number of contexts where the decision of the operator was the decision of its parent,
+    /// e.g. the rejecting operator of a rejected `all`. The top-level operators are decisive whenever evaluated.
+    /// A `not` is transparent, so the operator under it is decisive when it decides the opposite of the `not`.
+    pub decisive: usize,
+
+    pub decisions: BTreeMap<Decision, usize>,
+}
+
+impl Condition {
+    fn new(target: bool, location: Location, explanation: &Explanation) -> Self {
+        Self {
+            target,
+            location,
+            operator: explanation.operator,
+            r#where: explanation.r#where.clone(),
+            evaluated: 0,
+            decisive: 0,
+            decisions: BTreeMap::new(),
+        }
+    }
+
+    fn record(&mut self, explanation: &Explanation, parent: Option<Decision>) {
+        let decision = match explanation.decision {
+            Some(d) if !explanation.short_circuited => d,
+            _ => return,
+        };
+
+        self.evaluated += 1;
+        *self.decisions.entry(decision).or_default() += 1;
+        if parent.map_or(true, |p| p == decision) {
+            self.decisive += 1;
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.target {
+            write!(f, "target.")?;
+        }
+        write!(f, "{} {}", self.location, self.operator)?;
+        if let Some(w) = &self.r#where {
+            write!(f, " {}", w)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Coverage of a rule, with its operators in depth-first order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCoverage {
+    /// Number of contexts the rule was evaluated against without errors.
+    pub evaluated: usize,
+    pub errors: usize,
+    pub decisions: BTreeMap<Decision, usize>,
+    pub conditions: Vec<Condition>,
+}
+
+impl RuleCoverage {
+    fn new(rule: &Rule) -> Self {
+        let outline = rule.outline();
+        Self {
+            evaluated: 0,
+            errors: 0,
+            decisions: BTreeMap::new(),
+            conditions: nodes(rule, &outline)
+                .into_iter()
+                .map(|(target, location, e, _)| Condition::new(target, location, e))
+                .collect(),
+        }
+    }
+
+    fn record(&mut self, rule: &Rule, explanation: &Explanation) {
+        self.evaluated += 1;
+        if let Some(d) = explanation.decision {
+            *self.decisions.entry(d).or_default() += 1;
+        }
+
+        let nodes = nodes(rule, explanation);
+        for (condition, (_, _, e, parent)) in self.conditions.iter_mut().zip(nodes) {
+            condition.record(e, parent);
+        }
+    }
+}
+
+/// Coverage of the rules, accumulated by evaluating them against each context of a corpus.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    /// Coverage of each rule, in the order the rules are defined.
+    pub rules: IndexMap<String, RuleCoverage>,
+}
+
+impl Coverage {
+    /// Creates an empty coverage of the rules, where no operators are evaluated yet.
+    pub fn new(rules: &Rules) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .map(|(name, rule)| (name.clone(), RuleCoverage::new(rule)))
+                .collect(),
+        }
+    }
+
+    /// Evaluates the rules against the context, recording which operators were evaluated and decisive.
+    /// Errors are counted per rule instead of stopping the evaluation of the corpus.
+    pub fn record<C>(&mut self, rules: &Rules, context: &C, env: &Environment)
+    where
+        C: Context,
+    {
+        for (name, rule) in rules.iter() {
+            let coverage = self
+                .rules
+                .entry(name.clone())
+                .or_insert_with(|| RuleCoverage::new(rule));
+
+            let explanation = rule.explain_in(context, env);
+            match explanation.first_error() {
+                Some(_) => coverage.errors += 1,
+                _ => coverage.record(rule, &explanation),
+            }
+        }
+    }
+
+    /// Conditions never evaluated against any contexts.
+    pub fn unevaluated(&self) -> impl Iterator<Item = (&str, &Condition)> {
+        self.conditions().filter(|(_, c)| c.evaluated == 0)
+    }
+
+    /// Conditions evaluated but never decided the outcome of their parents.
+    pub fn indecisive(&self) -> impl Iterator<Item = (&str, &Condition)> {
+        self.conditions()
+            .filter(|(_, c)| c.evaluated > 0 && c.decisive == 0)
+    }
+
+    fn conditions(&self) -> impl Iterator<Item = (&str, &Condition)> {
+        self.rules
+            .iter()
+            .flat_map(|(name, r)| r.conditions.iter().map(move |c| (name.as_str(), c)))
+    }
+
+    /// Renders the coverage in the lcov tracefile format, attributed to the source file of the rules.
+    /// Rule documents carry no positions, so rules and their conditions are numbered as lines in order,
+    /// with the accepting and rejecting decisions of each condition as its branches.
+    pub fn to_lcov(&self, source: &str) -> String {
+        let mut lcov = format!("TN:\nSF:{}\n", source);
+        let mut line = 0;
+        let mut lines = vec![];
+        for (name, rule) in &self.rules {
+            line += 1;
+            let _ = writeln!(lcov, "FN:{},{}", line, name);
+            let _ = writeln!(lcov, "FNDA:{},{}", rule.evaluated, name);
+            for condition in &rule.conditions {
+                line += 1;
+                lines.push((line, condition));
+            }
+        }
+
+        let _ = writeln!(lcov, "FNF:{}", self.rules.len());
+        let _ = writeln!(
+            lcov,
+            "FNH:{}",
+            self.rules.values().filter(|r| r.evaluated > 0).count()
+        );
+
+        for (line, condition) in &lines {
+            for (branch, decision) in [Decision::Accept, Decision::Reject].iter().enumerate() {
+                let taken = match condition.evaluated {
+                    0 => "-".to_string(),
+                    _ => condition
+                        .decisions
+                        .get(decision)
+                        .copied()
+                        .unwrap_or(0)
+                        .to_string(),
+                };
+                let _ = writeln!(lcov, "BRDA:{},0,{},{}", line, branch, taken);
+            }
+        }
+        let taken = lines
+            .iter()
+            .map(|(_, c)| {
+                [Decision::Accept, Decision::Reject]
+                    .iter()
+                    .filter(|d| c.decisions.contains_key(d))
+                    .count()
+            })
+            .sum::<usize>();
+        let _ = writeln!(lcov, "BRF:{}", lines.len() * 2);
+        let _ = writeln!(lcov, "BRH:{}", taken);
+
+        for (line, condition) in &lines {
+            let _ = writeln!(lcov, "DA:{},{}", line, condition.evaluated);
+        }
+        let _ = writeln!(lcov, "LF:{}", lines.len());
+        let _ = writeln!(
+            lcov,
+            "LH:{}",
+            lines.iter().filter(|(_, c)| c.evaluated > 0).count()
+        );
+        lcov.push_str("end_of_record\n");
+
+        lcov
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (mut total, mut evaluated, mut decisive) = (0, 0, 0);
+        for (name, rule) in &self.rules {
+            write!(f, "{}: evaluated {} times", name, rule.evaluated)?;
+            for (decision, count) in &rule.decisions {
+                write!(f, ", {} {}", decision, count)?;
+            }
+            if rule.errors > 0 {
+                write!(f, ", {} errors", rule.errors)?;
+            }
+            writeln!(f)?;
+
+            for condition in &rule.conditions {
+                if condition.evaluated == 0 {
+                    writeln!(f, "  never evaluated: {}", condition)?;
+                } else if condition.decisive == 0 {
+                    writeln!(f, "  never decisive:  {}", condition)?;
+                }
+            }
+
+            total += rule.conditions.len();
+            evaluated += rule.conditions.iter().filter(|c| c.evaluated > 0).count();
+            decisive += rule.conditions.iter().filter(|c| c.decisive > 0).count();
+        }
+
+        write!(
+            f,
+            "\n{}/{} conditions evaluated, {}/{} decisive",
+            evaluated, total, decisive, total,
+        )
+    }
+}
+
+/// Evaluates the rules against all contexts, returning the coverage of the rules by them.
+pub fn measure<'a, C, I>(rules: &Rules, contexts: I, env: &Environment) -> Coverage
+where
+    C: Context + 'a,
+    I: IntoIterator<Item = &'a C>,
+{
+    let mut coverage = Coverage::new(rules);
+    for context in contexts {
+        coverage.record(rules, context, env);
+    }
+
+    coverage
+}
+
+type Node<'a> = (bool, Location, &'a Explanation, Option<Decision>);
+
+/// Flattens the explanation of the rule into its operators in depth-first order,
+/// with the decisions of their parents.
+fn nodes<'a>(rule: &Rule, explanation: &'a Explanation) -> Vec<Node<'a>> {
+    let roots = match (rule.target(), explanation.children.as_slice()) {
+        (Some(_), [target, operator]) => vec![(true, target), (false, operator)],
+        _ => vec![(false, explanation)],
+    };
+
+    let mut nodes = vec![];
+    for (target, root) in roots {
+        walk(target, Location::default(), root, None, &mut nodes);
+    }
+
+    nodes
+}
+
+fn walk<'a>(
+    target: bool,
+    location: Location,
+    explanation: &'a Explanation,
+    parent: Option<Decision>,
+    nodes: &mut Vec<Node<'a>>,
+) {
+    nodes.push((target, location.clone(), explanation, parent));
+
+    // The child of a `not` decides the opposite of it, which is compared instead.
+    let decision = match explanation.operator {
+        "not" => explanation.decision.map(|d| !d),
+        _ => explanation.decision,
+    };
+    for (i, child) in explanation.children.iter().enumerate() {
+        walk(target, location.child(i), child, decision, nodes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as kimari;
+
+    #[derive(kimari::Context)]
+    struct MyContext {
+        foo: String,
+        bar: i64,
+    }
+
+    #[test]
+    fn measure() {
+        // language=yaml
+        let rules = r#"
+        foo_or_bar:
+          target:
+            type: equals
+            where: foo
+            to: abc
+          type: any
+          operators:
+            - type: equals
+              where: bar
+              to: 456
+            - type: equals
+              where: bar
+              to: 123
+        foo_and_bar:
+          type: all
+          operators:
+            - type: equals
+              where: foo
+              to: abc
+            - type: equals
+              where: bar
+              to: 123
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(rules).unwrap();
+        let contexts = [
+            MyContext {
+                foo: "abc".to_string(),
+                bar: 123,
+            },
+            MyContext {
+                foo: "def".to_string(),
+                bar: 123,
+            },
+        ];
+        let coverage = super::measure(&rules, &contexts, &Environment::default());
+
+        let conditions = &coverage.rules["foo_and_bar"].conditions;
+        assert_eq!(
+            vec![(2, 2), (2, 2), (1, 1)],
+            conditions
+                .iter()
+                .map(|c| (c.evaluated, c.decisive))
+                .collect::<Vec<_>>(),
+        );
+
+        let conditions = &coverage.rules["foo_or_bar"].conditions;
+        assert_eq!(
+            vec![(2, 2), (1, 1), (1, 0), (1, 1)],
+            conditions
+                .iter()
+                .map(|c| (c.evaluated, c.decisive))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec![(
+                "foo_or_bar".to_string(),
+                "operators[0] equals bar".to_string()
+            )],
+            coverage
+                .indecisive()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(0, coverage.unevaluated().count());
+
+        assert_eq!(
+            r#"foo_or_bar: evaluated 2 times, accept 1, notApplicable 1
+  never decisive:  operators[0] equals bar
+foo_and_bar: evaluated 2 times, accept 1, reject 1
+
+7/7 conditions evaluated, 6/7 decisive"#,
+            coverage.to_string(),
+        );
+    }
+
+    #[test]
+    fn measure_not() {
+        // language=yaml
+        let rules = r#"
+        not_foo_and_bar:
+          type: not
+          operator:
+            type: all
+            operators:
+              - type: equals
+                where: foo
+                to: abc
+              - type: equals
+                where: bar
+                to: 123
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(rules).unwrap();
+        let contexts = [
+            MyContext {
+                foo: "abc".to_string(),
+                bar: 123,
+            },
+            MyContext {
+                foo: "abc".to_string(),
+                bar: 456,
+            },
+        ];
+        let coverage = super::measure(&rules, &contexts, &Environment::default());
+
+        let conditions = &coverage.rules["not_foo_and_bar"].conditions;
+        assert_eq!(
+            vec![(2, 2), (2, 2), (2, 1), (2, 2)],
+            conditions
+                .iter()
+                .map(|c| (c.evaluated, c.decisive))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(0, coverage.indecisive().count());
+    }
+
+    #[test]
+    fn unevaluated() {
+        // language=yaml
+        let rules = r#"
+        foo_or_bar:
+          type: any
+          operators:
+            - type: equals
+              where: foo
+              to: abc
+            - type: equals
+              where: bar
+              to: 123
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(rules).unwrap();
+        let contexts = [MyContext {
+            foo: "abc".to_string(),
+            bar: 123,
+        }];
+        let coverage = super::measure(&rules, &contexts, &Environment::default());
+
+        assert_eq!(
+            vec![(
+                "foo_or_bar".to_string(),
+                "operators[1] equals bar".to_string()
+            )],
+            coverage
+                .unevaluated()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            r#"TN:
+SF:rules.yaml
+FN:1,foo_or_bar
+FNDA:1,foo_or_bar
+FNF:1
+FNH:1
+BRDA:2,0,0,1
+BRDA:2,0,1,0
+BRDA:3,0,0,1
+BRDA:3,0,1,0
+BRDA:4,0,0,-
+BRDA:4,0,1,-
+BRF:6
+BRH:2
+DA:2,1
+DA:3,1
+DA:4,0
+LF:3
+LH:2
+end_of_record
+"#,
+            coverage.to_lcov("rules.yaml"),
+        );
+        assert_eq!(
+            serde_json::json!({
+                "rules": {
+                    "foo_or_bar": {
+                        "evaluated": 1,
+                        "errors": 0,
+                        "decisions": { "accept": 1 },
+                        "conditions": [
+                            {
+                                "target": false,
+                                "location": "root",
+                                "operator": "any",
+                                "evaluated": 1,
+                                "decisive": 1,
+                                "decisions": { "accept": 1 },
+                            },
+                            {
+                                "target": false,
+                                "location": "operators[0]",
+                                "operator": "equals",
+                                "where": "foo",
+                                "evaluated": 1,
+                                "decisive": 1,
+                                "decisions": { "accept": 1 },
+                            },
+                            {
+                                "target": false,
+                                "location": "operators[1]",
+                                "operator": "equals",
+                                "where": "bar",
+                                "evaluated": 0,
+                                "decisive": 0,
+                                "decisions": {},
+                            },
+                        ],
+                    },
+                },
+            }),
+            serde_json::to_value(&coverage).unwrap(),
+        );
+    }
+}
@@ -1,36 +1,91 @@
 use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Decision {
     Accept,
     Reject,
+
+    /// The rule does not apply to the context, e.g. its target did not match.
+    NotApplicable,
+
+    /// The decision could not be made, e.g. an attribute is absent in the context.
+    Indeterminate,
+}
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Accept => write!(f, "accept"),
+            Self::Reject => write!(f, "reject"),
+            Self::NotApplicable => write!(f, "notApplicable"),
+            Self::Indeterminate => write!(f, "indeterminate"),
+        }
+    }
 }
 
 impl Decision {
+    /// Combines two decisions as both of them are required to be accepted.
+    ///
+    /// `Reject` overrides anything, then `Indeterminate` overrides `Accept` and `NotApplicable`.
+    /// `NotApplicable` is neutral, so the other decision is taken as is.
+    pub fn and(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Reject, _) | (_, Self::Reject) => Self::Reject,
+            (Self::Indeterminate, _) | (_, Self::Indeterminate) => Self::Indeterminate,
+            (Self::NotApplicable, d) | (d, Self::NotApplicable) => d,
+            _ => Self::Accept,
+        }
+    }
+
+    /// Combines two decisions as either of them is required to be accepted.
+    ///
+    /// `Accept` overrides anything, then `Indeterminate` overrides `Reject` and `NotApplicable`.
+    /// `NotApplicable` is neutral, so the other decision is taken as is.
+    pub fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Accept, _) | (_, Self::Accept) => Self::Accept,
+            (Self::Indeterminate, _) | (_, Self::Indeterminate) => Self::Indeterminate,
+            (Self::NotApplicable, d) | (d, Self::NotApplicable) => d,
+            _ => Self::Reject,
+        }
+    }
+
     pub fn try_from_iter_all<I, E>(iter: I) -> Result<Self, E>
     where
         I: IntoIterator<Item = Result<Self, E>>,
     {
+        let mut combined = None;
         for decision in iter {
-            if decision? == Self::Reject {
+            let decision = decision?;
+            if decision == Self::Reject {
                 return Ok(Self::Reject);
             }
+
+            combined = Some(combined.map_or(decision, |d: Self| d.and(decision)));
         }
 
-        Ok(Self::Accept)
+        Ok(combined.unwrap_or(Self::Accept))
     }
 
     pub fn try_from_iter_any<I, E>(iter: I) -> Result<Self, E>
     where
         I: IntoIterator<Item = Result<Self, E>>,
     {
+        let mut combined = None;
         for decision in iter {
-            if decision? == Self::Accept {
+            let decision = decision?;
+            if decision == Self::Accept {
                 return Ok(Self::Accept);
             }
+
+            combined = Some(combined.map_or(decision, |d: Self| d.or(decision)));
         }
 
-        Ok(Self::Reject)
+        Ok(combined.unwrap_or(Self::Reject))
     }
 
     pub fn from_iter_all<I>(iter: I) -> Self
@@ -46,6 +101,53 @@ impl Decision {
     {
         Self::try_from_iter_any::<_, Infallible>(iter.into_iter().map(Ok)).unwrap()
     }
+
+    /// Converts the decision into a `bool`, resolving `NotApplicable` and `Indeterminate` by the policy.
+    pub fn to_bool(self, policy: BoolPolicy) -> bool {
+        match self {
+            Self::Accept => true,
+            Self::Reject => false,
+            Self::NotApplicable => policy.not_applicable,
+            Self::Indeterminate => policy.indeterminate,
+        }
+    }
+}
+
+/// Policy to convert a [`Decision`] into a `bool`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct BoolPolicy {
+    /// Value that `NotApplicable` is converted to.
+    pub not_applicable: bool,
+
+    /// Value that `Indeterminate` is converted to.
+    pub indeterminate: bool,
+}
+
+impl BoolPolicy {
+    /// Only `Accept` is converted to `true`.
+    pub const STRICT: Self = Self {
+        not_applicable: false,
+        indeterminate: false,
+    };
+
+    /// Only `Reject` is converted to `false`.
+    pub const PERMISSIVE: Self = Self {
+        not_applicable: true,
+        indeterminate: true,
+    };
+}
+
+impl std::ops::Not for Decision {
+    type Output = Self;
+
+    /// Swaps `Accept` and `Reject`, leaving `NotApplicable` and `Indeterminate` as they are.
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Accept => Self::Reject,
+            Self::Reject => Self::Accept,
+            d => d,
+        }
+    }
 }
 
 impl From<bool> for Decision {
@@ -58,10 +160,64 @@ impl From<bool> for Decision {
 }
 
 impl From<Decision> for bool {
+    /// Converts the decision into a `bool` with [`BoolPolicy::STRICT`].
     fn from(value: Decision) -> Self {
-        match value {
-            Decision::Accept => true,
-            _ => false,
-        }
+        value.to_bool(BoolPolicy::STRICT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Decision::*;
+
+    #[test]
+    fn from_iter_all() {
+        assert_eq!(Accept, Decision::from_iter_all([]));
+        assert_eq!(Accept, Decision::from_iter_all([Accept, NotApplicable]));
+        assert_eq!(
+            Reject,
+            Decision::from_iter_all([Accept, Indeterminate, Reject]),
+        );
+        assert_eq!(
+            Indeterminate,
+            Decision::from_iter_all([Accept, Indeterminate]),
+        );
+        assert_eq!(
+            NotApplicable,
+            Decision::from_iter_all([NotApplicable, NotApplicable]),
+        );
+    }
+
+    #[test]
+    fn from_iter_any() {
+        assert_eq!(Reject, Decision::from_iter_any([]));
+        assert_eq!(Reject, Decision::from_iter_any([Reject, NotApplicable]));
+        assert_eq!(
+            Accept,
+            Decision::from_iter_any([Reject, Indeterminate, Accept]),
+        );
+        assert_eq!(
+            Indeterminate,
+            Decision::from_iter_any([Reject, Indeterminate]),
+        );
+        assert_eq!(
+            NotApplicable,
+            Decision::from_iter_any([NotApplicable, NotApplicable]),
+        );
+    }
+
+    #[test]
+    fn to_bool() {
+        assert!(bool::from(Accept));
+        assert!(!bool::from(Reject));
+        assert!(!bool::from(NotApplicable));
+        assert!(!bool::from(Indeterminate));
+
+        assert!(Accept.to_bool(BoolPolicy::PERMISSIVE));
+        assert!(!Reject.to_bool(BoolPolicy::PERMISSIVE));
+        assert!(NotApplicable.to_bool(BoolPolicy::PERMISSIVE));
+        assert!(Indeterminate.to_bool(BoolPolicy::PERMISSIVE));
     }
 }
@@ -0,0 +1,502 @@
+//! Structural differences between two versions of rules, to review changes of rules by their meanings.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use crate::combining::Effect;
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::operator::{Location, Operate, Operator};
+use crate::rule::{Rule, Rules};
+
+/// Change of an operator within a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    Added(Box<Operator>),
+    Removed(Box<Operator>),
+    Replaced {
+        from: Box<Operator>,
+        to: Box<Operator>,
+    },
+}
+
+impl Display for Edit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added(o) => write!(f, "added condition {}", o),
+            Self::Removed(o) => write!(f, "removed condition {}", o),
+            Self::Replaced { from, to } => write!(f, "changed condition {} to {}", from, to),
+        }
+    }
+}
+
+/// Change between the old and the new rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+
+    /// The rule is renamed without changing its definition.
+    Renamed {
+        from: String,
+        to: String,
+    },
+
+    EffectChanged {
+        rule: String,
+        from: Effect,
+        to: Effect,
+    },
+
+    /// An operator is changed at the location, which is in the new rule except for removed operators.
+    Operator {
+        rule: String,
+        in_target: bool,
+        location: Location,
+        edit: Edit,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let effect = |e: &Effect| match e {
+            Effect::Permit => "permit",
+            Effect::Deny => "deny",
+        };
+
+        match self {
+            Self::Added(rule) => write!(f, "rule {}: added", rule),
+            Self::Removed(rule) => write!(f, "rule {}: removed", rule),
+            Self::Renamed { from, to } => write!(f, "rule {}: renamed to {}", from, to),
+            Self::EffectChanged { rule, from, to } => write!(
+                f,
+                "rule {}: changed effect {} to {}",
+                rule,
+                effect(from),
+                effect(to),
+            ),
+            Self::Operator {
+                rule,
+                in_target,
+                location,
+                edit,
+            } => write!(
+                f,
+                "rule {}: {} (at {}{})",
+                rule,
+                edit,
+                if *in_target { "target " } else { "" },
+                location,
+            ),
+        }
+    }
+}
+
+/// Compares the rules, reporting the changes in the order of the rule names.
+/// Removed and added rules with the same definitions are reported as renamed.
+pub fn diff(old: &Rules, new: &Rules) -> Vec<Change> {
+    let renames = renames(old, new);
+    let renamed = renames.values().collect::<BTreeSet<_>>();
+
+    let mut changes = vec![];
+    for (name, rule) in old.iter() {
+        if !new.contains_key(name) && !renamed.contains(name) {
+            changes.push(Change::Removed(name.clone()));
+        }
+
+        if let Some(r) = new.get(name) {
+            diff_rule(name, rule, r, &mut changes);
+        }
+    }
+
+    for name in new.keys() {
+        match renames.get(name) {
+            Some(from) => changes.push(Change::Renamed {
+                from: from.clone(),
+                to: name.clone(),
+            }),
+            _ if !old.contains_key(name) => changes.push(Change::Added(name.clone())),
+            _ => {}
+        }
+    }
+
+    changes.sort_by(|a, b| name_of(a).cmp(name_of(b)));
+    changes
+}
+
+fn name_of(change: &Change) -> &str {
+    match change {
+        Change::Added(rule) | Change::Removed(rule) => rule,
+        Change::Renamed { from, .. } => from,
+        Change::EffectChanged { rule, .. } | Change::Operator { rule, .. } => rule,
+    }
+}
+
+/// Maps the added rules to the removed rules they are renamed from.
+fn renames(old: &Rules, new: &Rules) -> BTreeMap<String, String> {
+    let mut removed = old
+        .iter()
+        .filter(|(name, _)| !new.contains_key(*name))
+        .collect::<Vec<_>>();
+
+    let mut renames = BTreeMap::new();
+    for (name, rule) in new.iter().filter(|(name, _)| !old.contains_key(*name)) {
+        // Normalizing could reorder the operators, which changes the errors raised, so rules are compared as is.
+        if let Some(i) = removed.iter().position(|(_, r)| *r == rule) {
+            let (from, _) = removed.remove(i);
+            renames.insert(name.clone(), from.clone());
+        }
+    }
+
+    renames
+}
+
+fn diff_rule(name: &str, old: &Rule, new: &Rule, changes: &mut Vec<Change>) {
+    if old.effect() != new.effect() {
+        changes.push(Change::EffectChanged {
+            rule: name.to_string(),
+            from: old.effect(),
+            to: new.effect(),
+        });
+    }
+
+    let mut edit = |in_target, location, edit| {
+        changes.push(Change::Operator {
+            rule: name.to_string(),
+            in_target,
+            location,
+            edit,
+        })
+    };
+
+    match (old.target(), new.target()) {
+        (Some(o), Some(n)) => {
+            diff_operator(o, n, Location::default(), &mut |l, e| edit(true, l, e))
+        }
+        (Some(o), None) => edit(
+            true,
+            Location::default(),
+            Edit::Removed(Box::new(o.clone())),
+        ),
+        (None, Some(n)) => edit(true, Location::default(), Edit::Added(Box::new(n.clone()))),
+        (None, None) => {}
+    }
+
+    diff_operator(
+        old.operator(),
+        new.operator(),
+        Location::default(),
+        &mut |l, e| edit(false, l, e),
+    );
+}
+
+/// Compares the operators at the location.
+/// Operators within `all` and `any` are matched regardless of their order,
+/// and the unmatched ones are compared with similar ones if any, otherwise reported as added or removed.
+fn diff_operator<F>(old: &Operator, new: &Operator, location: Location, edit: &mut F)
+where
+    F: FnMut(Location, Edit),
+{
+    if old == new {
+        return;
+    }
+
+    let (old_children, new_children) = match (old, new) {
+        (Operator::All(o), Operator::All(n)) => (&o.operators, &n.operators),
+        (Operator::Any(o), Operator::Any(n)) => (&o.operators, &n.operators),
+        (Operator::Not(o), Operator::Not(n)) => {
+            return diff_operator(&o.operator, &n.operator, location.child(0), edit);
+        }
+        _ => {
+            return edit(
+                location,
+                Edit::Replaced {
+                    from: Box::new(old.clone()),
+                    to: Box::new(new.clone()),
+                },
+            );
+        }
+    };
+
+    let mut removed = (0..old_children.len()).collect::<Vec<_>>();
+    let mut added = vec![];
+    for (i, child) in new_children.iter().enumerate() {
+        match removed.iter().position(|j| old_children[*j] == *child) {
+            Some(p) => {
+                removed.remove(p);
+            }
+            _ => added.push(i),
+        }
+    }
+
+    let mut paired = vec![];
+    for i in added.clone() {
+        if let Some(p) = removed
+            .iter()
+            .position(|j| similar(&old_children[*j], &new_children[i]))
+        {
+            paired.push((removed.remove(p), i));
+            added.retain(|a| *a != i);
+        }
+    }
+
+    for (j, i) in paired {
+        diff_operator(&old_children[j], &new_children[i], location.child(i), edit);
+    }
+    for j in removed {
+        edit(
+            location.child(j),
+            Edit::Removed(Box::new(old_children[j].clone())),
+        );
+    }
+    for i in added {
+        edit(
+            location.child(i),
+            Edit::Added(Box::new(new_children[i].clone())),
+        );
+    }
+}
+
+/// Whether the operators are of the same type looking up the same path, so that one is a change of the other.
+fn similar(a: &Operator, b: &Operator) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+        && a.outline().r#where == b.outline().r#where
+}
+
+/// Decision of a rule for a context, or why it was not decided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Decided(Decision),
+
+    /// The rule raised an error, with the message.
+    Error(String),
+
+    /// The rule does not exist in the version of the rules.
+    Missing,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Decided(d) => write!(f, "{}", d),
+            Self::Error(message) => write!(f, "error: {}", message),
+            Self::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// Context whose outcome of a rule differs between the old and the new rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutcomeChange {
+    /// Index of the context in the given contexts.
+    pub context: usize,
+
+    /// Name of the rule in the new rules, or in the old rules if removed.
+    pub rule: String,
+    pub old: Outcome,
+    pub new: Outcome,
+}
+
+impl Display for OutcomeChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "context #{}: rule {}: {} -> {}",
+            self.context, self.rule, self.old, self.new,
+        )
+    }
+}
+
+/// Evaluates both the old and the new rules against the sample contexts,
+/// reporting the contexts whose outcomes differ. Renamed rules are compared under their old names.
+pub fn outcome_changes<'a, C, I>(
+    old: &Rules,
+    new: &Rules,
+    contexts: I,
+    env: &Environment,
+) -> Vec<OutcomeChange>
+where
+    C: Context + 'a,
+    I: IntoIterator<Item = &'a C>,
+{
+    let renames = renames(old, new);
+    let renamed = renames.values().collect::<BTreeSet<_>>();
+
+    let mut pairs = new
+        .iter()
+        .map(|(name, rule)| {
+            let from = renames.get(name).unwrap_or(name);
+            (name, old.get(from), Some(rule))
+        })
+        .collect::<Vec<_>>();
+    pairs.extend(
+        old.iter()
+            .filter(|(name, _)| !new.contains_key(*name) && !renamed.contains(name))
+            .map(|(name, rule)| (name, Some(rule), None)),
+    );
+    pairs.sort_by_key(|(name, _, _)| *name);
+
+    let outcome = |rule: Option<&Rule>, context: &C| match rule {
+        Some(r) => match r.is_satisfied_by_in(context, env) {
+            Ok(d) => Outcome::Decided(d),
+            Err(e) => Outcome::Error(e.to_string()),
+        },
+        _ => Outcome::Missing,
+    };
+
+    let mut changes = vec![];
+    for (i, context) in contexts.into_iter().enumerate() {
+        for (name, old, new) in &pairs {
+            let (old, new) = (outcome(*old, context), outcome(*new, context));
+            if old != new {
+                changes.push(OutcomeChange {
+                    context: i,
+                    rule: name.to_string(),
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as kimari;
+
+    // language=yaml
+    const OLD: &str = r#"
+    eu_users:
+      type: all
+      operators:
+        - { type: equals, where: active, to: true }
+        - { type: notEquals, where: plan, to: free }
+    admins:
+      type: equals
+      where: role
+      to: admin
+    legacy:
+      type: equals
+      where: plan
+      to: legacy
+    "#;
+
+    // language=yaml
+    const NEW: &str = r#"
+    eu_users:
+      type: all
+      operators:
+        - { type: equals, where: region, to: eu }
+        - { type: notEquals, where: plan, to: trial }
+        - { type: equals, where: active, to: true }
+    administrators:
+      effect: deny
+      type: equals
+      where: role
+      to: admin
+    beta:
+      type: equals
+      where: plan
+      to: beta
+    "#;
+
+    #[derive(kimari::Context)]
+    struct User {
+        active: bool,
+        plan: String,
+        region: String,
+        role: String,
+    }
+
+    #[test]
+    fn diff() {
+        let (old, new) = (
+            serde_yaml::from_str::<Rules>(OLD).unwrap(),
+            serde_yaml::from_str::<Rules>(NEW).unwrap(),
+        );
+
+        assert_eq!(
+            vec![
+                "rule administrators: added",
+                "rule admins: removed",
+                "rule beta: added",
+                "rule eu_users: changed condition plan != free to plan != trial (at operators[1])",
+                "rule eu_users: added condition region == eu (at operators[0])",
+                "rule legacy: removed",
+            ],
+            super::diff(&old, &new)
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn diff_renamed() {
+        // language=yaml
+        let new = r#"
+        administrators:
+          type: equals
+          where: role
+          to: admin
+        active_paid_users:
+          type: all
+          operators:
+            - { type: notEquals, where: plan, to: free }
+            - { type: equals, where: active, to: true }
+        "#;
+
+        let (old, new) = (
+            serde_yaml::from_str::<Rules>(OLD).unwrap(),
+            serde_yaml::from_str::<Rules>(new).unwrap(),
+        );
+        let changes = super::diff(&old, &new);
+
+        assert!(changes.contains(&Change::Renamed {
+            from: "admins".to_string(),
+            to: "administrators".to_string(),
+        }));
+
+        // Reordering the operators may change which contexts raise errors, so it is not a rename.
+        assert!(changes.contains(&Change::Removed("eu_users".to_string())));
+        assert!(changes.contains(&Change::Added("active_paid_users".to_string())));
+    }
+
+    #[test]
+    fn outcome_changes() {
+        let (old, new) = (
+            serde_yaml::from_str::<Rules>(OLD).unwrap(),
+            serde_yaml::from_str::<Rules>(NEW).unwrap(),
+        );
+        let contexts = [
+            User {
+                active: true,
+                plan: "free".to_string(),
+                region: "eu".to_string(),
+                role: "user".to_string(),
+            },
+            User {
+                active: true,
+                plan: "pro".to_string(),
+                region: "us".to_string(),
+                role: "user".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            vec![
+                "context #0: rule eu_users: reject -> accept",
+                "context #1: rule eu_users: accept -> reject",
+            ],
+            super::outcome_changes(&old, &new, &contexts, &Environment::default())
+                .iter()
+                .filter(|c| c.rule == "eu_users")
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>(),
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt::{Debug, Formatter};
+use std::time::{Duration, SystemTime};
+
+/// Source of the current time, injected into the evaluation through [`Environment`].
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+/// Clock that reads the wall-clock time of the system.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock that always returns the same time, to evaluate rules deterministically in tests or to replay an evaluation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FixedClock(pub SystemTime);
+
+impl FixedClock {
+    /// Returns a clock fixed at the time advanced by the duration.
+    pub fn advance(self, duration: Duration) -> Self {
+        Self(self.0 + duration)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// Environment of an evaluation passed alongside the context, providing anything that does not come from the context.
+#[derive(Copy, Clone)]
+pub struct Environment<'a> {
+    clock: &'a dyn Clock,
+}
+
+impl Environment<'static> {
+    /// Environment with the system clock, used when no environment is given.
+    pub const SYSTEM: Self = Self {
+        clock: &SystemClock,
+    };
+}
+
+impl<'a> Environment<'a> {
+    /// Sets the clock that provides the current time.
+    pub fn with_clock(self, clock: &'a dyn Clock) -> Self {
+        Self { clock }
+    }
+
+    /// Returns the current time of the evaluation.
+    pub fn now(&self) -> SystemTime {
+        self.clock.now()
+    }
+}
+
+impl Default for Environment<'static> {
+    fn default() -> Self {
+        Self::SYSTEM
+    }
+}
+
+impl Debug for Environment<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Environment")
+            .field("now", &self.now())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_clock() {
+        let clock = FixedClock(SystemTime::UNIX_EPOCH);
+        let later = clock.advance(Duration::from_secs(60));
+
+        assert_eq!(
+            SystemTime::UNIX_EPOCH,
+            Environment::default().with_clock(&clock).now(),
+        );
+        assert_eq!(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(60),
+            Environment::default().with_clock(&later).now(),
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::operator::Error;
+use crate::rule::Rule;
+
+/// Policy to handle errors raised while evaluating rules.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorPolicy {
+    /// Stops the evaluation and returns the first error.
+    #[default]
+    FailFast,
+
+    /// Treats the errored rules as rejected, discarding the errors.
+    RejectOnError,
+
+    /// Treats the errored rules as rejected, collecting the errors into the evaluation.
+    Collect,
+}
+
+/// Result of evaluating all rules against a context.
+#[derive(Debug, Default)]
+pub struct Evaluation<'a> {
+    /// Rules that are satisfied by the context, in the order they are defined.
+    pub matched: Vec<(&'a str, &'a Rule)>,
+
+    /// Rules that raised an error with the error, in the order they are defined.
+    pub errors: Vec<(&'a str, Error)>,
+}
+
+impl<'a> Evaluation<'a> {
+    /// Returns the first rule that is satisfied by the context.
+    pub fn first(&self) -> Option<(&'a str, &'a Rule)> {
+        self.matched.first().copied()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
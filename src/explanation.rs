@@ -0,0 +1,222 @@
+use serde::Serialize;
+
+use crate::decision::Decision;
+use crate::operator::Error;
+use crate::value::Value;
+
+/// A trace of an evaluation, mirroring the structure of the evaluated operators.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Explanation {
+    /// Type of the operator, as written in the rule document.
+    pub operator: &'static str,
+
+    /// Path in the context the operator looked up, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#where: Option<String>,
+
+    /// Value resolved from the context at the path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<Value>,
+
+    /// Value the resolved value is compared to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Value>,
+
+    /// Decision of the operator, or `None` if it was not evaluated or an error was raised within it.
+    pub decision: Option<Decision>,
+
+    /// Error raised by the operator itself, e.g. failing to look up the context.
+    /// The operators containing it have no decision, and the ones after it are not evaluated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Whether the operator was skipped because the outcome was already decided.
+    pub short_circuited: bool,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Explanation>,
+}
+
+impl Explanation {
+    /// Creates an unevaluated explanation of an operator without children.
+    pub fn new(operator: &'static str) -> Self {
+        Self {
+            operator,
+            r#where: None,
+            actual: None,
+            expected: None,
+            decision: None,
+            error: None,
+            short_circuited: false,
+            children: vec![],
+        }
+    }
+
+    /// Creates an unevaluated explanation of an operator comparing a value in the context.
+    pub fn comparison(operator: &'static str, r#where: &str, expected: &Value) -> Self {
+        Self {
+            r#where: Some(r#where.to_string()),
+            expected: Some(expected.clone()),
+            ..Self::new(operator)
+        }
+    }
+
+    pub fn with_children(self, children: Vec<Explanation>) -> Self {
+        Self { children, ..self }
+    }
+
+    pub fn with_actual(self, actual: Value) -> Self {
+        Self {
+            actual: Some(actual),
+            ..self
+        }
+    }
+
+    pub fn with_decision(self, decision: Decision) -> Self {
+        Self {
+            decision: Some(decision),
+            ..self
+        }
+    }
+
+    pub fn with_error(self, error: &Error) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..self
+        }
+    }
+
+    /// Returns the first error raised within the operator in depth-first order, if any.
+    pub fn first_error(&self) -> Option<&str> {
+        self.error
+            .as_deref()
+            .or_else(|| self.children.iter().find_map(|c| c.first_error()))
+    }
+
+    /// Marks the operator as skipped, because the outcome was decided by its siblings.
+    pub fn short_circuited(self) -> Self {
+        Self {
+            short_circuited: true,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::Environment;
+    use crate::operator::{All, Any, Equals, NotEquals, Operate, Operator};
+
+    #[test]
+    fn explain_short_circuit() {
+        let context = vec![123, 456];
+        let operator = Any {
+            operators: vec![
+                Operator::Equals(Equals {
+                    r#where: "0".to_string(),
+                    to: Value::from(123),
+                }),
+                Operator::NotEquals(NotEquals {
+                    r#where: "1".to_string(),
+                    to: Value::from(456),
+                }),
+            ],
+        };
+
+        assert_eq!(
+            Explanation::new("any")
+                .with_children(vec![
+                    Explanation::comparison("equals", "0", &Value::from(123))
+                        .with_actual(Value::from(123))
+                        .with_decision(Decision::Accept),
+                    Explanation::comparison("notEquals", "1", &Value::from(456)).short_circuited(),
+                ])
+                .with_decision(Decision::Accept),
+            operator.explain(&context, &Environment::default()),
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let context = vec![123, 456];
+        let operator = All {
+            operators: vec![Operator::Equals(Equals {
+                r#where: "1".to_string(),
+                to: Value::from(123),
+            })],
+        };
+
+        assert_eq!(
+            serde_json::json!({
+                "operator": "all",
+                "decision": "reject",
+                "shortCircuited": false,
+                "children": [
+                    {
+                        "operator": "equals",
+                        "where": "1",
+                        "actual": 456,
+                        "expected": 123,
+                        "decision": "reject",
+                        "shortCircuited": false,
+                    },
+                ],
+            }),
+            serde_json::to_value(operator.explain(&context, &Environment::default())).unwrap(),
+        );
+    }
+
+    #[test]
+    fn explain_error() {
+        let context = vec![123, 456];
+        let equals = |r#where: &str, to: i32| {
+            Operator::Equals(Equals {
+                r#where: r#where.to_string(),
+                to: Value::from(to),
+            })
+        };
+        let operator = All {
+            operators: vec![equals("0", 123), equals("abc", 1), equals("1", 456)],
+        };
+
+        let error =
+            "Unexpected context: Unexpected index in an array: abc (invalid digit found in string)";
+        let explanation = operator.explain(&context, &Environment::default());
+
+        assert_eq!(
+            Explanation::new("all").with_children(vec![
+                Explanation::comparison("equals", "0", &Value::from(123))
+                    .with_actual(Value::from(123))
+                    .with_decision(Decision::Accept),
+                Explanation {
+                    error: Some(error.to_string()),
+                    ..Explanation::comparison("equals", "abc", &Value::from(1))
+                },
+                Explanation::comparison("equals", "1", &Value::from(456)),
+            ]),
+            explanation,
+        );
+        assert_eq!(Some(error), explanation.first_error());
+    }
+
+    #[test]
+    fn explain_default() {
+        struct Always;
+
+        impl Operate for Always {
+            fn operate<C>(&self, _: &C, _: &Environment) -> Result<Decision, Error>
+            where
+                C: crate::context::Context,
+            {
+                Ok(Decision::Accept)
+            }
+        }
+
+        let explanation = Always.explain(&123, &Environment::default());
+
+        assert!(explanation.operator.ends_with("Always"));
+        assert_eq!(Some(Decision::Accept), explanation.decision);
+    }
+}
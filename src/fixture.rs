@@ -0,0 +1,337 @@
+//! Test fixtures of rules, written by the rule authors to cover changes of the rules.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::evaluation::ErrorPolicy;
+use crate::rule::Rules;
+
+/// Context with the outcome expected from evaluating the rules against it.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Fixture<C> {
+    pub name: String,
+    pub context: C,
+
+    /// Names of all rules expected to be matched, if checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched: Option<BTreeSet<String>>,
+
+    /// Decisions expected from some of the rules.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub decisions: BTreeMap<String, Decision>,
+
+    /// Current time of the evaluation, fixed so that the temporal operators decide the same way on every run.
+    #[cfg(feature = "chrono")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub now: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+/// Difference between the expected and actual outcomes of a fixture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// Rules expected to be matched but not, and rules matched but not expected.
+    Matched {
+        missing: Vec<String>,
+        unexpected: Vec<String>,
+    },
+
+    Decision {
+        rule: String,
+        expected: Decision,
+        actual: Decision,
+    },
+
+    /// A rule in the expected decisions does not exist.
+    UnknownRule(String),
+
+    /// A rule raised an error, with the message.
+    Error(String),
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Matched {
+                missing,
+                unexpected,
+            } => {
+                write!(f, "matched rules differ (- expected, + actual):")?;
+                for name in missing {
+                    write!(f, "\n  - {}", name)?;
+                }
+                for name in unexpected {
+                    write!(f, "\n  + {}", name)?;
+                }
+                Ok(())
+            }
+            Self::Decision {
+                rule,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "decision of {} differs:\n  - {}\n  + {}",
+                rule, expected, actual,
+            ),
+            Self::UnknownRule(rule) => write!(f, "unknown rule: {}", rule),
+            Self::Error(message) => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/// Outcome of a fixture, passed if there are no mismatches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub name: String,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.passed() {
+            return write!(f, "ok   {}", self.name);
+        }
+
+        write!(f, "FAIL {}", self.name)?;
+        for mismatch in &self.mismatches {
+            for line in mismatch.to_string().lines() {
+                write!(f, "\n    {}", line)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcomes of all fixtures, printed as a report suitable for CI logs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.outcomes.iter().all(|o| o.passed())
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for outcome in &self.outcomes {
+            writeln!(f, "{}", outcome)?;
+        }
+
+        let failed = self.outcomes.iter().filter(|o| !o.passed()).count();
+        write!(
+            f,
+            "\n{} passed, {} failed",
+            self.outcomes.len() - failed,
+            failed,
+        )
+    }
+}
+
+impl<C> Fixture<C>
+where
+    C: Context,
+{
+    /// Evaluates the rules against the context of the fixture, comparing the outcome with the expected one.
+    /// If the fixture fixes the current time, it overrides the clock of the environment.
+    pub fn run(&self, rules: &Rules, env: &Environment) -> Outcome {
+        #[cfg(feature = "chrono")]
+        if let Some(now) = self.now {
+            return self.compare(
+                rules,
+                &env.with_clock(&crate::environment::FixedClock(now.into())),
+            );
+        }
+
+        self.compare(rules, env)
+    }
+
+    fn compare(&self, rules: &Rules, env: &Environment) -> Outcome {
+        let mut mismatches = vec![];
+
+        if let Some(expected) = &self.matched {
+            match rules.evaluate_in(&self.context, env, ErrorPolicy::Collect) {
+                Ok(evaluation) => {
+                    mismatches.extend(
+                        evaluation
+                            .errors
+                            .iter()
+                            .map(|(_, e)| Mismatch::Error(e.to_string())),
+                    );
+
+                    let actual = evaluation
+                        .matched
+                        .iter()
+                        .map(|(name, _)| name.to_string())
+                        .collect::<BTreeSet<_>>();
+                    if &actual != expected {
+                        mismatches.push(Mismatch::Matched {
+                            missing: expected.difference(&actual).cloned().collect(),
+                            unexpected: actual.difference(expected).cloned().collect(),
+                        });
+                    }
+                }
+                Err(e) => mismatches.push(Mismatch::Error(e.to_string())),
+            }
+        }
+
+        for (name, expected) in &self.decisions {
+            let rule = match rules.get(name) {
+                Some(r) => r,
+                _ => {
+                    mismatches.push(Mismatch::UnknownRule(name.clone()));
+                    continue;
+                }
+            };
+
+            match rule.is_satisfied_by_in(&self.context, env) {
+                Ok(actual) if actual == *expected => {}
+                Ok(actual) => mismatches.push(Mismatch::Decision {
+                    rule: name.clone(),
+                    expected: *expected,
+                    actual,
+                }),
+                Err(e) => mismatches.push(Mismatch::Error(e.in_rule(name).to_string())),
+            }
+        }
+
+        Outcome {
+            name: self.name.clone(),
+            mismatches,
+        }
+    }
+}
+
+/// Runs all fixtures against the rules.
+pub fn run<'a, C, I>(rules: &Rules, fixtures: I, env: &Environment) -> Report
+where
+    C: Context + 'a,
+    I: IntoIterator<Item = &'a Fixture<C>>,
+{
+    Report {
+        outcomes: fixtures.into_iter().map(|f| f.run(rules, env)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as kimari;
+
+    #[derive(Debug, PartialEq, Deserialize, kimari::Context)]
+    struct MyContext {
+        foo: String,
+        bar: i64,
+    }
+
+    #[test]
+    fn run() {
+        // language=yaml
+        let rules = r#"
+        foo_is_abc:
+          type: equals
+          where: foo
+          to: abc
+        bar_is_123:
+          type: equals
+          where: bar
+          to: 123
+        "#;
+
+        // language=yaml
+        let fixtures = r#"
+        - name: passes
+          context: { foo: abc, bar: 123 }
+          matched: [bar_is_123, foo_is_abc]
+          decisions:
+            foo_is_abc: accept
+        - name: fails
+          context: { foo: def, bar: 123 }
+          matched: [foo_is_abc]
+          decisions:
+            bar_is_123: reject
+            baz: accept
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(rules).unwrap();
+        let fixtures = serde_yaml::from_str::<Vec<Fixture<MyContext>>>(fixtures).unwrap();
+        let report = super::run(&rules, &fixtures, &Environment::default());
+
+        assert!(!report.passed());
+        assert_eq!(
+            vec![
+                Mismatch::Matched {
+                    missing: vec!["foo_is_abc".to_string()],
+                    unexpected: vec!["bar_is_123".to_string()],
+                },
+                Mismatch::Decision {
+                    rule: "bar_is_123".to_string(),
+                    expected: Decision::Reject,
+                    actual: Decision::Accept,
+                },
+                Mismatch::UnknownRule("baz".to_string()),
+            ],
+            report.outcomes[1].mismatches,
+        );
+        assert_eq!(
+            r#"ok   passes
+FAIL fails
+    matched rules differ (- expected, + actual):
+      - foo_is_abc
+      + bar_is_123
+    decision of bar_is_123 differs:
+      - reject
+      + accept
+    unknown rule: baz
+
+1 passed, 1 failed"#,
+            report.to_string(),
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn run_now() {
+        // language=yaml
+        let rules = r#"
+        recent:
+          type: withinLast
+          where: foo
+          duration: 1h
+        "#;
+
+        // language=yaml
+        let fixtures = r#"
+        - name: within
+          context: { foo: "2024-01-01T00:00:00Z", bar: 0 }
+          now: 2024-01-01T00:30:00Z
+          decisions:
+            recent: accept
+        - name: after
+          context: { foo: "2024-01-01T00:00:00Z", bar: 0 }
+          now: 2024-01-01T03:00:00+01:00
+          decisions:
+            recent: reject
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(rules).unwrap();
+        let fixtures = serde_yaml::from_str::<Vec<Fixture<MyContext>>>(fixtures).unwrap();
+        let report = super::run(&rules, &fixtures, &Environment::default());
+
+        assert!(report.passed(), "{}", report);
+    }
+}
@@ -0,0 +1,293 @@
+//! Feature flags evaluated into variants by ordered targeting rules, falling through to a default.
+
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::rule::Rule;
+use crate::value::Value;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Unknown flag: {0}")]
+    UnknownFlag(String),
+
+    #[error("Error in targeting rule {index}: {source}")]
+    InTargeting {
+        index: usize,
+        #[source]
+        source: crate::operator::Error,
+    },
+
+    #[error("Error in flag {key}: {source}")]
+    InFlag {
+        key: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+/// Targeting rule of a flag, serving the variant if the rule is satisfied.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Targeting {
+    pub variant: Value,
+
+    #[serde(flatten)]
+    pub rule: Rule,
+}
+
+/// Feature flag with its targeting rules, which are evaluated in order.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Flag {
+    /// Variant served if no targeting rules are satisfied.
+    pub default: Value,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Targeting>,
+}
+
+/// Reason why the variant of a flag was chosen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Reason {
+    /// The targeting rule at the index was the first one satisfied.
+    RuleMatch { index: usize },
+
+    /// No targeting rules were satisfied, so the default variant was served.
+    Default,
+
+    /// No targeting rules were satisfied, but the one at the index was the first that could not be decided,
+    /// e.g. as an attribute it looks up is absent, so the default variant was served.
+    Indeterminate { index: usize },
+}
+
+/// Variant of a flag chosen for a context, with the reason.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub struct FlagEvaluation<'a> {
+    pub variant: &'a Value,
+    pub reason: Reason,
+}
+
+impl Flag {
+    /// Choose the variant for the context, from the first targeting rule satisfied by it.
+    /// Targeting rules that cannot be decided are skipped, which the reason reports if the default is served.
+    /// Errors are attributed to the targeting rules by their indices.
+    ///
+    /// The order is that of [`Combining::FirstApplicable`](crate::Combining::FirstApplicable),
+    /// except that only a satisfied rule applies: rules that are not satisfied or cannot be decided fall through.
+    pub fn evaluate<C>(&self, context: &C, env: &Environment) -> Result<FlagEvaluation<'_>, Error>
+    where
+        C: Context,
+    {
+        let mut indeterminate = None;
+        for (index, targeting) in self.rules.iter().enumerate() {
+            let decision = targeting
+                .rule
+                .is_satisfied_by_in(context, env)
+                .map_err(|source| Error::InTargeting { index, source })?;
+            match decision {
+                Decision::Accept => {
+                    return Ok(FlagEvaluation {
+                        variant: &targeting.variant,
+                        reason: Reason::RuleMatch { index },
+                    });
+                }
+                Decision::Indeterminate if indeterminate.is_none() => indeterminate = Some(index),
+                _ => {}
+            }
+        }
+
+        Ok(FlagEvaluation {
+            variant: &self.default,
+            reason: match indeterminate {
+                Some(index) => Reason::Indeterminate { index },
+                _ => Reason::Default,
+            },
+        })
+    }
+}
+
+/// Feature flags keyed by their names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Flags {
+    #[serde(flatten)]
+    map: BTreeMap<String, Flag>,
+}
+
+impl Flags {
+    /// Choose the variant of the flag for the context, with the reason it was chosen.
+    pub fn evaluate_flag<C>(&self, key: &str, context: &C) -> Result<FlagEvaluation<'_>, Error>
+    where
+        C: Context,
+    {
+        self.evaluate_flag_in(key, context, &Environment::SYSTEM)
+    }
+
+    /// Choose the variant of the flag for the context in the environment, like [`Flags::evaluate_flag`].
+    pub fn evaluate_flag_in<C>(
+        &self,
+        key: &str,
+        context: &C,
+        env: &Environment,
+    ) -> Result<FlagEvaluation<'_>, Error>
+    where
+        C: Context,
+    {
+        let flag = self
+            .map
+            .get(key)
+            .ok_or_else(|| Error::UnknownFlag(key.to_string()))?;
+
+        flag.evaluate(context, env).map_err(|source| Error::InFlag {
+            key: key.to_string(),
+            source: Box::new(source),
+        })
+    }
+}
+
+impl Deref for Flags {
+    type Target = BTreeMap<String, Flag>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for Flags {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as kimari;
+
+    #[derive(kimari::Context)]
+    struct User {
+        id: String,
+        country: String,
+    }
+
+    #[test]
+    fn evaluate_flag() {
+        // language=yaml
+        let yaml = r#"
+        checkout-color:
+          default: blue
+          rules:
+            - variant: red
+              type: equals
+              where: country
+              to: jp
+            - variant: green
+              type: bucket
+              where: id
+              to: 50
+        "#;
+
+        let flags = serde_yaml::from_str::<Flags>(yaml).unwrap();
+        let user = |id: &str, country: &str| User {
+            id: id.to_string(),
+            country: country.to_string(),
+        };
+
+        let evaluation = flags
+            .evaluate_flag("checkout-color", &user("alice", "jp"))
+            .unwrap();
+        assert_eq!(&Value::from("red"), evaluation.variant);
+        assert_eq!(Reason::RuleMatch { index: 0 }, evaluation.reason);
+
+        let variants = (0..100)
+            .map(|i| {
+                flags
+                    .evaluate_flag("checkout-color", &user(&i.to_string(), "us"))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(variants
+            .iter()
+            .any(|e| e.variant == &Value::from("green")
+                && e.reason == Reason::RuleMatch { index: 1 }));
+        assert!(variants
+            .iter()
+            .any(|e| e.variant == &Value::from("blue") && e.reason == Reason::Default));
+
+        assert!(matches!(
+            flags.evaluate_flag("unknown", &user("alice", "jp")),
+            Err(Error::UnknownFlag(k)) if k == "unknown",
+        ));
+    }
+
+    #[test]
+    fn evaluate_flag_indeterminate() {
+        // language=yaml
+        let yaml = r#"
+        foo:
+          default: 0
+          rules:
+            - variant: 1
+              type: equals
+              where: country
+              to: us
+            - variant: 2
+              type: equals
+              where: unknown
+              to: 1
+            - variant: 3
+              type: equals
+              where: id
+              to: bob
+        "#;
+
+        let flags = serde_yaml::from_str::<Flags>(yaml).unwrap();
+        let user = |id: &str| User {
+            id: id.to_string(),
+            country: "jp".to_string(),
+        };
+
+        let evaluation = flags.evaluate_flag("foo", &user("alice")).unwrap();
+        assert_eq!(&Value::from(0), evaluation.variant);
+        assert_eq!(Reason::Indeterminate { index: 1 }, evaluation.reason);
+
+        let evaluation = flags.evaluate_flag("foo", &user("bob")).unwrap();
+        assert_eq!(&Value::from(3), evaluation.variant);
+        assert_eq!(Reason::RuleMatch { index: 2 }, evaluation.reason);
+
+        assert_eq!(
+            r#"{"type":"indeterminate","index":1}"#,
+            serde_json::to_string(&Reason::Indeterminate { index: 1 }).unwrap(),
+        );
+    }
+
+    #[test]
+    fn evaluate_flag_error() {
+        // language=yaml
+        let yaml = r#"
+        foo:
+          default: 0
+          rules:
+            - variant: 1
+              type: equals
+              where: unknown
+              to: 1
+        "#;
+
+        let flags = serde_yaml::from_str::<Flags>(yaml).unwrap();
+        let error = flags.evaluate_flag("foo", &vec!["alice"]).unwrap_err();
+
+        assert_eq!(
+            "Error in flag foo: Error in targeting rule 0: Unexpected context: Unexpected index in an array: unknown (invalid digit found in string)",
+            error.to_string(),
+        );
+        assert!(matches!(
+            error,
+            Error::InFlag { source, .. } if matches!(*source, Error::InTargeting { index: 0, .. }),
+        ));
+    }
+}
@@ -0,0 +1,591 @@
+//! JSON Schema of the rule document format, for editors to complete and validate rules.
+
+use serde_json::{json, Map, Value as Json};
+
+use crate::context::Context;
+use crate::schema::Schema;
+
+/// Maximum depth of nested structs to enumerate the paths, so that recursive structs terminate.
+const MAX_DEPTH: usize = 8;
+
+/// Generates the JSON Schema of [`crate::Rules`], accepting any path in `where`.
+pub fn rules() -> Json {
+    rules_with_path(json!({ "type": "string" }))
+}
+
+/// Generates the JSON Schema of [`crate::Rules`], accepting only the valid paths of the context in `where`.
+pub fn rules_for<C>() -> Json
+where
+    C: Context,
+{
+    let mut paths = vec![];
+    collect_paths(&C::schema(), &[], 0, &mut paths);
+    paths.retain(|p| !p.is_empty());
+
+    let names = paths.iter().filter_map(|p| to_name(p)).collect::<Vec<_>>();
+    let patterns = paths.iter().map(|p| to_pattern(p)).collect::<Vec<_>>();
+    let pattern = format!("^({})$", patterns.join("|"));
+
+    rules_with_path(json!({
+        "type": "string",
+        "anyOf": [
+            { "enum": names },
+            { "pattern": pattern },
+        ],
+    }))
+}
+
+/// Generates the JSON Schema of [`crate::Operator`], accepting any path in `where`.
+pub fn operator() -> Json {
+    let rules = rules();
+
+    json!({
+        "$schema": rules["$schema"],
+        "title": "Operator",
+        "allOf": [{ "$ref": "#/definitions/operator" }],
+        "definitions": rules["definitions"],
+    })
+}
+
+fn rules_with_path(path: Json) -> Json {
+    let mut definitions = operators();
+    let names = definitions.keys().cloned().collect::<Vec<_>>();
+
+    definitions.insert(
+        "operator".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type"],
+            "oneOf": names
+                .iter()
+                .map(|n| json!({ "$ref": format!("#/definitions/{}", n) }))
+                .collect::<Vec<_>>(),
+        }),
+    );
+
+    // Rules are operators with the fields of the rule, which are additional properties to the operators.
+    let rules = names
+        .iter()
+        .map(|n| {
+            let mut rule = definitions[n].clone();
+            rule["properties"]["target"] = json!({ "$ref": "#/definitions/operator" });
+            rule["properties"]["effect"] = json!({ "enum": ["permit", "deny"] });
+            rule
+        })
+        .collect::<Vec<_>>();
+    definitions.insert(
+        "rule".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type"],
+            "oneOf": rules,
+        }),
+    );
+    definitions.insert("path".to_string(), path);
+    definitions.insert(
+        "value".to_string(),
+        json!({
+            "anyOf": [
+                { "type": "boolean" },
+                { "type": "integer" },
+                { "type": "string" },
+                { "type": "array", "items": { "$ref": "#/definitions/value" } },
+                { "type": "null" },
+            ],
+        }),
+    );
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Rules",
+        "type": "object",
+        "additionalProperties": { "$ref": "#/definitions/rule" },
+        "definitions": definitions,
+    })
+}
+
+/// Definitions of each operator, keyed by its `type`.
+fn operators() -> Map<String, Json> {
+    let operators = json!({
+        "type": "array",
+        "items": { "$ref": "#/definitions/operator" },
+    });
+    let comparison = |r#type: &str| {
+        json!({
+            "type": "object",
+            "required": ["type", "where", "to"],
+            "properties": {
+                "type": { "const": r#type },
+                "where": { "$ref": "#/definitions/path" },
+                "to": { "$ref": "#/definitions/value" },
+            },
+        })
+    };
+    let network = |r#type: &str| {
+        json!({
+            "type": "object",
+            "required": ["type", "where", "networks"],
+            "properties": {
+                "type": { "const": r#type },
+                "where": { "$ref": "#/definitions/path" },
+                "networks": {
+                    "type": "array",
+                    "items": { "type": "string", "description": "CIDR block, e.g. 10.0.0.0/8" },
+                },
+            },
+        })
+    };
+
+    let mut map = Map::new();
+    map.insert(
+        "all".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "operators"],
+            "properties": {
+                "type": { "const": "all" },
+                "operators": operators,
+            },
+        }),
+    );
+    map.insert(
+        "any".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "operators"],
+            "properties": {
+                "type": { "const": "any" },
+                "operators": operators,
+            },
+        }),
+    );
+    map.insert(
+        "not".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "operator"],
+            "properties": {
+                "type": { "const": "not" },
+                "operator": { "$ref": "#/definitions/operator" },
+            },
+        }),
+    );
+    map.insert("equals".to_string(), comparison("equals"));
+    map.insert("notEquals".to_string(), comparison("notEquals"));
+    map.insert("inNetwork".to_string(), network("inNetwork"));
+    map.insert("notInNetwork".to_string(), network("notInNetwork"));
+    map.insert(
+        "bucket".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "where", "to"],
+            "properties": {
+                "type": { "const": "bucket" },
+                "where": { "$ref": "#/definitions/path" },
+                "salt": { "type": "string" },
+                "from": { "type": "integer", "minimum": 0, "maximum": 100 },
+                "to": { "type": "integer", "minimum": 0, "maximum": 100 },
+            },
+        }),
+    );
+
+    #[cfg(feature = "chrono")]
+    temporal_operators(&mut map);
+    #[cfg(feature = "semver")]
+    version_operators(&mut map);
+
+    // Misspelled fields are rejected, so that editors point them out.
+    for definition in map.values_mut() {
+        definition["additionalProperties"] = json!(false);
+    }
+
+    map
+}
+
+/// Definitions of the temporal operators, enabled by the `chrono` feature.
+#[cfg(feature = "chrono")]
+fn temporal_operators(map: &mut Map<String, Json>) {
+    let time_of_day = json!({ "type": "string", "pattern": "^\\d{2}:\\d{2}(:\\d{2})?$" });
+    let timezone =
+        json!({ "type": "string", "description": "Name in the IANA time zone database" });
+
+    // Days are parsed case-insensitively, by their names or the abbreviations.
+    let weekday = [
+        "monday",
+        "tuesday",
+        "wednesday",
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+    ]
+    .iter()
+    .map(|d| {
+        let (abbreviation, rest) = d.split_at(3);
+        format!(
+            "{}({})?",
+            case_insensitive(abbreviation),
+            case_insensitive(rest)
+        )
+    })
+    .collect::<Vec<_>>();
+    let weekday = format!("^({})$", weekday.join("|"));
+    let timestamp = |r#type: &str| {
+        json!({
+            "type": "object",
+            "required": ["type", "where", "to"],
+            "properties": {
+                "type": { "const": r#type },
+                "where": { "$ref": "#/definitions/path" },
+                "to": { "type": "string", "format": "date-time" },
+            },
+        })
+    };
+
+    map.insert("before".to_string(), timestamp("before"));
+    map.insert("after".to_string(), timestamp("after"));
+    map.insert(
+        "withinLast".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "where", "duration"],
+            "properties": {
+                "type": { "const": "withinLast" },
+                "where": { "$ref": "#/definitions/path" },
+                "duration": { "type": "string", "pattern": "^(\\d+[wdhms])+$" },
+            },
+        }),
+    );
+    map.insert(
+        "dayOfWeek".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "where", "days"],
+            "properties": {
+                "type": { "const": "dayOfWeek" },
+                "where": { "$ref": "#/definitions/path" },
+                "days": {
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                        "anyOf": [
+                            { "enum": ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] },
+                            { "pattern": weekday },
+                        ],
+                    },
+                },
+                "timezone": timezone,
+            },
+        }),
+    );
+    map.insert(
+        "timeOfDay".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "where", "from", "to"],
+            "properties": {
+                "type": { "const": "timeOfDay" },
+                "where": { "$ref": "#/definitions/path" },
+                "from": time_of_day,
+                "to": time_of_day,
+                "timezone": timezone,
+            },
+        }),
+    );
+}
+
+/// Pattern matching the text in any case, as the patterns of JSON Schema have no flags.
+#[cfg(feature = "chrono")]
+fn case_insensitive(text: &str) -> String {
+    text.chars()
+        .map(|c| format!("[{}{}]", c.to_ascii_uppercase(), c))
+        .collect()
+}
+
+/// Definitions of the semantic version operators, enabled by the `semver` feature.
+#[cfg(feature = "semver")]
+fn version_operators(map: &mut Map<String, Json>) {
+    map.insert(
+        "versionCompare".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "where", "comparison", "to"],
+            "properties": {
+                "type": { "const": "versionCompare" },
+                "where": { "$ref": "#/definitions/path" },
+                "comparison": { "enum": ["lt", "lte", "eq", "ne", "gte", "gt"] },
+                "to": { "type": "string", "description": "Semantic version, e.g. 3.2.0" },
+            },
+        }),
+    );
+    map.insert(
+        "versionMatches".to_string(),
+        json!({
+            "type": "object",
+            "required": ["type", "where", "requirement"],
+            "properties": {
+                "type": { "const": "versionMatches" },
+                "where": { "$ref": "#/definitions/path" },
+                "requirement": { "type": "string", "description": "Version requirement, e.g. ^3.2" },
+            },
+        }),
+    );
+}
+
+/// Segment of a valid path in a context.
+#[derive(Clone)]
+enum Segment {
+    Name(&'static str),
+
+    /// Index of an element in a tuple, which is valid only up to its length.
+    Position(usize),
+    Index,
+    Key,
+    Any,
+}
+
+/// Collects the valid paths in the schema.
+fn collect_paths(schema: &Schema, prefix: &[Segment], depth: usize, paths: &mut Vec<Vec<Segment>>) {
+    let join = |segment: Segment| {
+        let mut path = prefix.to_vec();
+        path.push(segment);
+        path
+    };
+
+    match schema {
+        Schema::Any => paths.push(join(Segment::Any)),
+        Schema::Boolean | Schema::Integer | Schema::String | Schema::IpAddr => {
+            paths.push(prefix.to_vec())
+        }
+        #[cfg(feature = "chrono")]
+        Schema::Timestamp | Schema::Duration => paths.push(prefix.to_vec()),
+        Schema::Array(inner) => {
+            paths.push(prefix.to_vec());
+            collect_paths(inner, &join(Segment::Index), depth, paths);
+        }
+        Schema::Tuple(elements) => {
+            paths.push(prefix.to_vec());
+            for (i, element) in elements.iter().enumerate() {
+                collect_paths(element, &join(Segment::Position(i)), depth, paths);
+            }
+        }
+        Schema::Optional(inner) => collect_paths(inner, prefix, depth, paths),
+        Schema::Map(inner) => collect_paths(inner, &join(Segment::Key), depth, paths),
+        Schema::Struct(fields) if depth < MAX_DEPTH => {
+            for field in fields {
+                collect_paths(
+                    &(field.schema)(),
+                    &join(Segment::Name(field.name)),
+                    depth + 1,
+                    paths,
+                );
+            }
+        }
+        Schema::Struct(_) => {}
+    }
+}
+
+/// Renders the path as is, if it does not contain any placeholders.
+fn to_name(path: &[Segment]) -> Option<String> {
+    path.iter()
+        .map(|s| match s {
+            Segment::Name(n) => Some(n.to_string()),
+            Segment::Position(i) => Some(i.to_string()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|s| s.join("."))
+}
+
+/// Renders the path as a regular expression.
+fn to_pattern(path: &[Segment]) -> String {
+    path.iter()
+        .map(|s| match s {
+            Segment::Name(n) => n.to_string(),
+            Segment::Position(i) => i.to_string(),
+            Segment::Index => "\\d+".to_string(),
+            Segment::Key => "[^.]+".to_string(),
+            Segment::Any => ".+".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\\.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::tests::MyContext;
+
+    #[test]
+    fn rules() {
+        let schema = super::rules();
+
+        #[allow(unused_mut)]
+        let mut names = vec![
+            "all",
+            "any",
+            "bucket",
+            "equals",
+            "inNetwork",
+            "not",
+            "notEquals",
+            "notInNetwork",
+        ];
+        #[cfg(feature = "chrono")]
+        names.extend(["after", "before", "dayOfWeek", "timeOfDay", "withinLast"]);
+        #[cfg(feature = "semver")]
+        names.extend(["versionCompare", "versionMatches"]);
+        names.sort();
+
+        assert_eq!(
+            json!(names),
+            json!(schema["definitions"]["operator"]["oneOf"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["$ref"]
+                    .as_str()
+                    .unwrap()
+                    .trim_start_matches("#/definitions/"))
+                .collect::<Vec<_>>()),
+        );
+        assert_eq!(json!({ "type": "string" }), schema["definitions"]["path"],);
+    }
+
+    #[test]
+    fn rules_for() {
+        let schema = super::rules_for::<MyContext>();
+
+        assert_eq!(
+            json!({
+                "type": "string",
+                "anyOf": [
+                    { "enum": ["foo", "bar"] },
+                    { "pattern": "^(foo|bar|bar\\.\\d+)$" },
+                ],
+            }),
+            schema["definitions"]["path"],
+        );
+
+        let schema = super::rules_for::<(usize, Vec<String>)>();
+
+        assert_eq!(
+            json!({
+                "type": "string",
+                "anyOf": [
+                    { "enum": ["0", "1"] },
+                    { "pattern": "^(0|1|1\\.\\d+)$" },
+                ],
+            }),
+            schema["definitions"]["path"],
+        );
+    }
+
+    #[test]
+    fn operator_validates_serialized() {
+        #[allow(unused_mut)]
+        // language=yaml
+        let mut yaml = r#"
+        - type: all
+          operators:
+            - { type: equals, where: foo, to: [1, abc, null] }
+            - { type: notEquals, where: foo, to: true }
+        - type: any
+          operators:
+            - { type: inNetwork, where: ip, networks: [10.0.0.0/8, 2001:db8::/32] }
+            - { type: notInNetwork, where: ip, networks: [192.0.2.1] }
+        - type: not
+          operator: { type: bucket, where: user.id, salt: checkout, from: 10, to: 20 }
+        "#
+        .to_string();
+        #[cfg(feature = "chrono")]
+        yaml.push_str(
+            // language=yaml
+            r#"
+        - { type: before, where: t, to: 2024-01-01T00:00:00+09:00 }
+        - { type: after, where: t, to: 2024-01-01T00:00:00Z }
+        - { type: withinLast, where: t, duration: 1d12h }
+        - { type: dayOfWeek, where: t, days: [mon, Tuesday, SAT], timezone: Asia/Tokyo }
+        - { type: timeOfDay, where: t, from: "09:00", to: "17:30:15", timezone: Asia/Tokyo }
+            "#,
+        );
+        #[cfg(feature = "semver")]
+        yaml.push_str(
+            // language=yaml
+            r#"
+        - { type: versionCompare, where: v, comparison: gte, to: 1.2.3-beta.1+build.5 }
+        - { type: versionMatches, where: v, requirement: ">=1.2, <2" }
+            "#,
+        );
+
+        let operators = serde_yaml::from_str::<Vec<crate::Operator>>(&yaml).unwrap();
+        let inputs = serde_yaml::from_str::<Vec<Json>>(&yaml).unwrap();
+        let schema = jsonschema::JSONSchema::compile(&super::operator()).unwrap();
+
+        // Both the operators as written and as serialized are valid.
+        let mut types = vec![];
+        for json in operators
+            .iter()
+            .map(|o| serde_json::to_value(o).unwrap())
+            .chain(inputs)
+        {
+            if let Err(errors) = schema.validate(&json) {
+                panic!(
+                    "{}: {}",
+                    json,
+                    errors.map(|e| e.to_string()).collect::<Vec<_>>().join(", "),
+                );
+            }
+
+            collect_types(&json, &mut types);
+        }
+        types.sort();
+        types.dedup();
+
+        // Every operator is serialized and validated at least once.
+        assert_eq!(
+            super::operators().keys().cloned().collect::<Vec<_>>(),
+            types,
+        );
+    }
+
+    #[test]
+    fn rules_reject_unknown_fields() {
+        // language=yaml
+        let yaml = r#"
+        valid:
+          type: equals
+          where: foo
+          to: 1
+          target: { type: equals, where: bar, to: 2 }
+          effect: deny
+        misspelled_where: { type: equals, wher: foo, to: 1 }
+        misspelled_to: { type: equals, where: foo, too: 1 }
+        nested_target:
+          type: not
+          operator: { type: equals, where: foo, to: 1, target: { type: equals, where: bar, to: 2 } }
+        "#;
+
+        let rules = serde_yaml::from_str::<Map<String, Json>>(yaml).unwrap();
+        let schema = jsonschema::JSONSchema::compile(&super::rules()).unwrap();
+        let valid = |name: &str| schema.is_valid(&json!({ name: rules[name] }));
+
+        assert!(valid("valid"));
+        assert!(!valid("misspelled_where"));
+        assert!(!valid("misspelled_to"));
+        assert!(!valid("nested_target"));
+    }
+
+    fn collect_types(json: &Json, types: &mut Vec<String>) {
+        if let Some(t) = json["type"].as_str() {
+            types.push(t.to_string());
+        }
+        for key in ["operators", "operator"] {
+            match &json[key] {
+                Json::Array(operators) => operators.iter().for_each(|o| collect_types(o, types)),
+                Json::Object(_) => collect_types(&json[key], types),
+                _ => {}
+            }
+        }
+    }
+}
@@ -1,13 +1,37 @@
+pub mod analysis;
+mod combining;
 pub mod context;
+pub mod coverage;
 mod decision;
+pub mod diff;
+mod environment;
+mod evaluation;
+mod explanation;
+pub mod fixture;
+pub mod flags;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
+pub mod network;
+pub mod normalize;
 pub mod operator;
+pub mod plan;
 mod rule;
+pub mod schema;
+#[cfg(feature = "chrono")]
+mod time;
 mod value;
+#[cfg(feature = "semver")]
+mod version;
 
-pub use decision::Decision;
+pub use combining::{Combining, Effect};
+pub use decision::{BoolPolicy, Decision};
+pub use environment::{Clock, Environment, FixedClock, SystemClock};
+pub use evaluation::{ErrorPolicy, Evaluation};
+pub use explanation::Explanation;
+pub use flags::Flags;
 pub use operator::{Operate, Operator};
 pub use rule::{Rule, Rules};
-pub use value::Value;
+pub use value::{Value, ValueRef};
 
 pub use kimari_derive::Context;
 
@@ -69,11 +93,208 @@ mod tests {
         let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
 
         assert_eq!(
-            vec!["bar_array_is_456_and_789", "foo_is_123"],
+            vec!["foo_is_123", "bar_array_is_456_and_789"],
             rules
                 .find_all(&ctx)
                 .map(|r| r.unwrap().0)
                 .collect::<Vec<_>>(),
         )
     }
+
+    #[test]
+    fn derive_std_types() {
+        use std::borrow::Cow;
+        use std::collections::HashMap;
+        use std::rc::Rc;
+
+        #[derive(Context)]
+        struct NestedContext {
+            id: u8,
+        }
+
+        #[derive(Context)]
+        struct MyContext<'a> {
+            name: Cow<'a, str>,
+            nested: Box<NestedContext>,
+            shared: Rc<[NestedContext]>,
+            labels: HashMap<String, &'a str>,
+            pair: (char, i16),
+        }
+
+        let ctx = MyContext {
+            name: Cow::Borrowed("abc"),
+            nested: Box::new(NestedContext { id: 1 }),
+            shared: Rc::from([NestedContext { id: 2 }]),
+            labels: HashMap::from([("env".to_string(), "prod")]),
+            pair: ('x', -1),
+        };
+
+        assert_eq!(Value::from("abc"), ctx.get_from_context_at("name").unwrap());
+        assert_eq!(
+            Value::from(1),
+            ctx.get_from_context_at("nested.id").unwrap()
+        );
+        assert_eq!(
+            Value::from(2),
+            ctx.get_from_context_at("shared.0.id").unwrap(),
+        );
+        assert_eq!(
+            Value::from("prod"),
+            ctx.get_from_context_at("labels.env").unwrap(),
+        );
+        assert_eq!(Value::from(-1), ctx.get_from_context_at("pair.1").unwrap());
+        assert!(matches!(
+            ctx.get_from_context_at("nested").unwrap_err(),
+            context::Error::UnsupportedValue(..),
+        ));
+
+        assert!(matches!(
+            ctx.get_ref_from_context_at("labels.env").unwrap(),
+            ValueRef::String(Cow::Borrowed("prod")),
+        ));
+        assert_eq!(
+            ctx.get_ref_from_context_at("shared.0.id").unwrap(),
+            Value::from(2),
+        );
+        assert!(matches!(
+            ctx.get_ref_from_context_at("nested").unwrap_err(),
+            context::Error::UnsupportedValue(..),
+        ));
+
+        // Looking up the paths at once agrees with looking them up one by one, including the errors.
+        let paths = [
+            "nested.id",
+            "labels.env",
+            "nested",
+            "shared.0.id",
+            "labels.unknown",
+            "unknown.id",
+            "nested.id.unknown",
+        ];
+        let segments = paths
+            .iter()
+            .map(|p| p.split('.').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths
+                .iter()
+                .map(|p| {
+                    ctx.get_ref_from_context_at(p)
+                        .map(Value::from)
+                        .map_err(|e| e.to_string())
+                })
+                .collect::<Vec<_>>(),
+            ctx.get_refs_from_context(&segments.iter().map(|s| s.as_slice()).collect::<Vec<_>>())
+                .into_iter()
+                .map(|r| r.map(Value::from).map_err(|e| e.to_string()))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn error_context() {
+        #[derive(Context)]
+        struct NestedContext {
+            baz: String,
+            qux: Vec<String>,
+        }
+
+        #[derive(Context)]
+        struct MyContext {
+            foo: usize,
+            bar: NestedContext,
+        }
+
+        let ctx = MyContext {
+            foo: 123,
+            bar: NestedContext {
+                baz: "abc".to_string(),
+                qux: vec![],
+            },
+        };
+
+        // language=yaml
+        let yaml = r#"
+        bar_qux_is_def:
+          type: any
+          operators:
+            - type: equals
+              where: foo
+              to: 456
+            - type: all
+              operators:
+                - type: equals
+                  where: bar.baz
+                  to: abc
+                - type: equals
+                  where: bar.qux.first
+                  to: def
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
+        let error = rules.find(&ctx).unwrap_err();
+
+        assert_eq!(Some("bar_qux_is_def"), error.rule());
+        assert_eq!(operator::Location(vec![1, 1]), error.location());
+        assert_eq!(
+            "Error in rule bar_qux_is_def: Unexpected context: Unexpected index in an array: bar.qux.first (invalid digit found in string) (at operators[1].operators[1])",
+            error.to_string(),
+        );
+    }
+
+    #[test]
+    fn validate() {
+        #[derive(Context)]
+        struct NestedContext {
+            baz: String,
+            array: Vec<usize>,
+        }
+
+        #[derive(Context)]
+        struct MyContext {
+            foo: usize,
+            bar: Option<NestedContext>,
+        }
+
+        // language=yaml
+        let yaml = r#"
+        valid:
+          type: all
+          operators:
+            - type: equals
+              where: foo
+              to: 123
+            - type: equals
+              where: bar.array.0
+              to: 456
+            - type: notEquals
+              where: bar
+              to: ~
+        invalid:
+          target:
+            type: equals
+            where: bar.bazz
+            to: abc
+          type: any
+          operators:
+            - type: equals
+              where: foo
+              to: abc
+            - type: equals
+              where: bar.array.first
+              to: 456
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
+        let errors = rules.validate::<MyContext>().unwrap_err();
+
+        assert_eq!(
+            vec![
+                "Invalid rule invalid: Unknown path: bar.bazz (at target root)",
+                "Invalid rule invalid: Value String(\"abc\") cannot be compared to foo of type integer (at operators[0])",
+                "Invalid rule invalid: Unexpected index in an array: bar.array.first (at operators[1])",
+            ],
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+        );
+    }
 }
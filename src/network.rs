@@ -0,0 +1,360 @@
+//! CIDR blocks of IP addresses and sets of them, used by the network operators.
+
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::value::ValueRef;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid IP address in CIDR block: {0}")]
+    InvalidAddress(String),
+
+    #[error("Invalid prefix length in CIDR block: {0}")]
+    InvalidPrefix(String),
+}
+
+/// Block of IP addresses written in the CIDR notation, e.g. `10.0.0.0/8` or `2001:db8::/32`.
+/// Bits of the address beyond the prefix are cleared, and an address without a prefix is a block of itself.
+/// Blocks of IPv4-mapped IPv6 addresses, e.g. `::ffff:10.0.0.0/104`, are normalized into IPv4 blocks like the addresses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    address: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn new(address: IpAddr, prefix: u8) -> Option<Self> {
+        let (address, prefix) = match address {
+            IpAddr::V6(a) if prefix >= 96 => match a.to_ipv4_mapped() {
+                Some(a) => (IpAddr::V4(a), prefix - 96),
+                _ => (address, prefix),
+            },
+            _ => (address, prefix),
+        };
+
+        let (bits, length) = to_bits(address);
+        if prefix > length {
+            return None;
+        }
+
+        let mask = match prefix {
+            0 => 0,
+            p => u128::MAX << (128 - p),
+        };
+
+        Some(Self {
+            address: from_bits(bits & mask, address.is_ipv4()),
+            prefix,
+        })
+    }
+
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// Whether the address is within the block.
+    pub fn contains(&self, address: IpAddr) -> bool {
+        Self::new(normalize(address), self.prefix) == Some(*self)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = match s.split_once('/') {
+            Some((a, p)) => (a, Some(p)),
+            _ => (s, None),
+        };
+
+        let address =
+            IpAddr::from_str(address).map_err(|_| Error::InvalidAddress(s.to_string()))?;
+        let prefix = match prefix {
+            Some(p) => u8::from_str(p).map_err(|_| Error::InvalidPrefix(s.to_string()))?,
+            _ if address.is_ipv4() => 32,
+            _ => 128,
+        };
+
+        Self::new(address, prefix).ok_or_else(|| Error::InvalidPrefix(s.to_string()))
+    }
+}
+
+impl Display for Cidr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+impl Serialize for Cidr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cidr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Node of a binary trie over the bits of addresses.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: [Option<usize>; 2],
+
+    /// Whether a block ends at the node, so that all addresses below it are contained.
+    terminal: bool,
+}
+
+/// Binary trie of the blocks of either IPv4 or IPv6 addresses.
+/// Looking up an address takes at most as many steps as the bits of the address, regardless of the number of blocks.
+#[derive(Debug, Clone)]
+struct Trie {
+    nodes: Vec<Node>,
+}
+
+impl Trie {
+    fn new() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+        }
+    }
+
+    /// Inserts the prefix of the bits, aligned to the most significant bit.
+    fn insert(&mut self, bits: u128, prefix: u8) {
+        let mut node = 0;
+        for i in 0..prefix {
+            if self.nodes[node].terminal {
+                return;
+            }
+
+            let bit = (bits >> (127 - i) & 1) as usize;
+            node = match self.nodes[node].children[bit] {
+                Some(n) => n,
+                _ => {
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children[bit] = Some(self.nodes.len() - 1);
+                    self.nodes.len() - 1
+                }
+            };
+        }
+
+        self.nodes[node].terminal = true;
+        self.nodes[node].children = [None, None];
+    }
+
+    /// Whether any inserted prefix is a prefix of the bits.
+    fn contains(&self, bits: u128, length: u8) -> bool {
+        let mut node = 0;
+        for i in 0..length {
+            if self.nodes[node].terminal {
+                return true;
+            }
+
+            let bit = (bits >> (127 - i) & 1) as usize;
+            node = match self.nodes[node].children[bit] {
+                Some(n) => n,
+                _ => return false,
+            };
+        }
+
+        self.nodes[node].terminal
+    }
+}
+
+/// Set of CIDR blocks, matching addresses with prefix tries for IPv4 and IPv6 respectively.
+/// IPv4-mapped IPv6 addresses are matched as IPv4 addresses.
+#[derive(Debug, Clone)]
+pub struct NetworkSet {
+    blocks: Vec<Cidr>,
+    v4: Trie,
+    v6: Trie,
+}
+
+impl NetworkSet {
+    pub fn blocks(&self) -> &[Cidr] {
+        &self.blocks
+    }
+
+    /// Whether the address is within any of the blocks.
+    pub fn contains(&self, address: IpAddr) -> bool {
+        let address = normalize(address);
+        let (bits, length) = to_bits(address);
+        match address {
+            IpAddr::V4(_) => self.v4.contains(bits, length),
+            IpAddr::V6(_) => self.v6.contains(bits, length),
+        }
+    }
+}
+
+impl FromIterator<Cidr> for NetworkSet {
+    fn from_iter<T: IntoIterator<Item = Cidr>>(iter: T) -> Self {
+        let blocks = iter.into_iter().collect::<Vec<_>>();
+        let (mut v4, mut v6) = (Trie::new(), Trie::new());
+        for block in &blocks {
+            let (bits, _) = to_bits(block.address);
+            match block.address {
+                IpAddr::V4(_) => v4.insert(bits, block.prefix),
+                IpAddr::V6(_) => v6.insert(bits, block.prefix),
+            }
+        }
+
+        Self { blocks, v4, v6 }
+    }
+}
+
+impl PartialEq for NetworkSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.blocks == other.blocks
+    }
+}
+
+impl Eq for NetworkSet {}
+
+impl Serialize for NetworkSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.blocks.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NetworkSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<Cidr>::deserialize(deserializer).map(|blocks| blocks.into_iter().collect())
+    }
+}
+
+/// Returns the bits of the address aligned to the most significant bit, with the length of the address.
+fn to_bits(address: IpAddr) -> (u128, u8) {
+    match address {
+        IpAddr::V4(a) => ((u32::from(a) as u128) << 96, 32),
+        IpAddr::V6(a) => (u128::from(a), 128),
+    }
+}
+
+fn from_bits(bits: u128, ipv4: bool) -> IpAddr {
+    match ipv4 {
+        true => IpAddr::from(Ipv4Addr::from((bits >> 96) as u32)),
+        false => IpAddr::from(Ipv6Addr::from(bits)),
+    }
+}
+
+/// Converts IPv4-mapped IPv6 addresses into IPv4 addresses.
+fn normalize(address: IpAddr) -> IpAddr {
+    match address {
+        IpAddr::V6(a) => match a.to_ipv4_mapped() {
+            Some(a) => IpAddr::V4(a),
+            _ => IpAddr::V6(a),
+        },
+        a => a,
+    }
+}
+
+/// Resolves an IP address from the value, parsing strings.
+pub(crate) fn to_ip_addr(value: &ValueRef) -> Option<IpAddr> {
+    match value {
+        ValueRef::IpAddr(a) => Some(*a),
+        ValueRef::String(s) => IpAddr::from_str(s).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        IpAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn cidr() {
+        let cidr = Cidr::from_str("10.1.2.3/8").unwrap();
+
+        assert_eq!("10.0.0.0/8", cidr.to_string());
+        assert!(cidr.contains(ip("10.255.0.1")));
+        assert!(cidr.contains(ip("::ffff:10.0.0.1")));
+        assert!(!cidr.contains(ip("11.0.0.0")));
+        assert_eq!(
+            "2001:db8::/32",
+            Cidr::from_str("2001:db8::1/32").unwrap().to_string()
+        );
+        assert_eq!(
+            "192.0.2.1/32",
+            Cidr::from_str("192.0.2.1").unwrap().to_string()
+        );
+        assert_eq!(
+            "0.0.0.0/0",
+            Cidr::from_str("0.0.0.0/0").unwrap().to_string()
+        );
+        assert_eq!(
+            "10.0.0.0/8",
+            Cidr::from_str("::ffff:10.1.2.3/104").unwrap().to_string()
+        );
+        assert_eq!(
+            Err(Error::InvalidPrefix("::ffff:10.0.0.0/129".to_string())),
+            Cidr::from_str("::ffff:10.0.0.0/129"),
+        );
+        assert_eq!(
+            Err(Error::InvalidPrefix("10.0.0.0/33".to_string())),
+            Cidr::from_str("10.0.0.0/33"),
+        );
+        assert_eq!(
+            Err(Error::InvalidAddress("10.0.0/8".to_string())),
+            Cidr::from_str("10.0.0/8"),
+        );
+    }
+
+    #[test]
+    fn network_set() {
+        let set = [
+            "10.0.0.0/8",
+            "10.1.0.0/16",
+            "192.168.1.0/24",
+            "2001:db8::/32",
+            "::ffff:172.16.0.0/108",
+        ]
+        .iter()
+        .map(|s| Cidr::from_str(s).unwrap())
+        .collect::<NetworkSet>();
+
+        assert!(set.contains(ip("10.1.2.3")));
+        assert!(set.contains(ip("10.200.0.1")));
+        assert!(set.contains(ip("192.168.1.255")));
+        assert!(!set.contains(ip("192.168.2.0")));
+        assert!(set.contains(ip("2001:db8:ffff::1")));
+        assert!(!set.contains(ip("2001:db9::1")));
+        assert!(!set.contains(ip("::ffff:192.168.2.0")));
+        assert!(set.contains(ip("172.31.255.255")));
+        assert!(set.contains(ip("::ffff:172.16.0.1")));
+        assert!(!set.contains(ip("172.32.0.0")));
+
+        let all = ["0.0.0.0/0"]
+            .iter()
+            .map(|s| Cidr::from_str(s).unwrap())
+            .collect::<NetworkSet>();
+
+        assert!(all.contains(ip("255.255.255.255")));
+        assert!(!all.contains(ip("::1")));
+    }
+}
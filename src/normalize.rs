@@ -0,0 +1,449 @@
+//! Rewriting of operators into canonical simplified forms, so that rules can be compared and stored compactly.
+//!
+//! Within an operator tree, decisions are `Accept`, `Reject` or `Indeterminate`, combined as a three-valued logic.
+//! The rewrites are the laws that hold in the logic, such as De Morgan's laws, absorption and distribution,
+//! so a normalized operator makes the same decision as the original one for any context where no operator raises
+//! an error. `x` or `not x` is not a tautology in the logic, as it is indeterminate if `x` is, so it is kept as is.
+//!
+//! Errors are not part of the logic. Evaluation stops at the first error unless `all` or `any` is already decided
+//! by an earlier operator, and normalizing reorders, removes and duplicates the operators within them.
+//! So for a context where an operator raises an error, the normalized operator may raise it where the original one
+//! makes a decision, e.g. `all` of a rejected leaf before an erroring one, and vice versa.
+
+use std::cmp::Ordering;
+
+use crate::operator::{All, Any, Equals, Not, NotEquals, Operator};
+use crate::rule::Rule;
+use crate::value::Value;
+
+/// Rewrites the operator into a canonical simplified form.
+///
+/// - Negations are pushed down to the leaves, and `not` of `equals` is turned into `notEquals` and vice versa.
+/// - Nested `all` within `all` and `any` within `any` are flattened.
+/// - `all` and `any` of a single operator are replaced with the operator.
+/// - Duplicated operators are removed, and the remaining ones are sorted.
+/// - Constants are folded, where `all: []` is always accepted and `any: []` is always rejected.
+/// - Operators absorbed by their siblings are removed, e.g. `x` and (`x` or `y`) is `x`.
+pub fn normalize(operator: &Operator) -> Operator {
+    simplify(push_negations(operator, false))
+}
+
+/// Rewrites the operator into the disjunctive normal form, which is `any` of `all` of leaves, simplified.
+/// The number of operators can grow exponentially.
+pub fn to_dnf(operator: &Operator) -> Operator {
+    let clauses = clauses(&normalize(operator), true);
+    simplify(any(clauses.into_iter().map(all).collect()))
+}
+
+/// Rewrites the operator into the conjunctive normal form, which is `all` of `any` of leaves, simplified.
+/// The number of operators can grow exponentially.
+pub fn to_cnf(operator: &Operator) -> Operator {
+    let clauses = clauses(&normalize(operator), false);
+    simplify(all(clauses.into_iter().map(any).collect()))
+}
+
+/// Normalizes the target and the operator of the rule, keeping its effect.
+pub fn normalize_rule(rule: &Rule) -> Rule {
+    let normalized = Rule::new(normalize(rule.operator())).with_effect(rule.effect());
+    match rule.target() {
+        Some(t) => normalized.with_target(normalize(t)),
+        _ => normalized,
+    }
+}
+
+fn all(operators: Vec<Operator>) -> Operator {
+    Operator::All(All { operators })
+}
+
+fn any(operators: Vec<Operator>) -> Operator {
+    Operator::Any(Any { operators })
+}
+
+/// Rewrites the operator so that `not` is applied only to leaves, negating it if `negated`.
+fn push_negations(operator: &Operator, negated: bool) -> Operator {
+    let children = |operators: &[Operator]| {
+        operators
+            .iter()
+            .map(|o| push_negations(o, negated))
+            .collect()
+    };
+
+    match (operator, negated) {
+        (Operator::All(o), false) => all(children(&o.operators)),
+        (Operator::All(o), true) => any(children(&o.operators)),
+        (Operator::Any(o), false) => any(children(&o.operators)),
+        (Operator::Any(o), true) => all(children(&o.operators)),
+        (Operator::Not(o), _) => push_negations(&o.operator, !negated),
+        (Operator::Equals(o), true) => Operator::NotEquals(NotEquals {
+            r#where: o.r#where.clone(),
+            to: o.to.clone(),
+        }),
+        (Operator::NotEquals(o), true) => Operator::Equals(Equals {
+            r#where: o.r#where.clone(),
+            to: o.to.clone(),
+        }),
+        (o, true) => Operator::Not(Not {
+            operator: Box::new(o.clone()),
+        }),
+        (o, false) => o.clone(),
+    }
+}
+
+/// Simplifies the operator whose negations are already pushed down to the leaves.
+fn simplify(operator: Operator) -> Operator {
+    match operator {
+        Operator::All(o) => simplify_junction(o.operators, true),
+        Operator::Any(o) => simplify_junction(o.operators, false),
+        o => o,
+    }
+}
+
+/// Returns the operators of `all` with `true`, or those of `any` with `false`.
+fn junction(operator: &Operator) -> Option<(&[Operator], bool)> {
+    match operator {
+        Operator::All(o) => Some((&o.operators, true)),
+        Operator::Any(o) => Some((&o.operators, false)),
+        _ => None,
+    }
+}
+
+/// Creates `all` of the operators if `conjunction`, otherwise `any` of them.
+fn junction_of(operators: Vec<Operator>, conjunction: bool) -> Operator {
+    match conjunction {
+        true => Operator::All(All { operators }),
+        false => Operator::Any(Any { operators }),
+    }
+}
+
+/// Simplifies `all` of the operators if `conjunction`, otherwise `any` of them.
+fn simplify_junction(operators: Vec<Operator>, conjunction: bool) -> Operator {
+    let mut flattened = vec![];
+    for operator in operators.into_iter().map(simplify) {
+        match junction(&operator) {
+            // The same junction is flattened, where the empty one is its identity.
+            Some((children, c)) if c == conjunction => flattened.extend(children.iter().cloned()),
+            // The empty dual junction is the constant absorbing the junction.
+            Some(([], _)) => return junction_of(vec![], !conjunction),
+            _ => flattened.push(operator),
+        }
+    }
+
+    // Operators may be ordered the same without being equal, so duplicates are not necessarily adjacent.
+    flattened.sort_by(compare);
+    let mut unique = Vec::with_capacity(flattened.len());
+    for operator in flattened {
+        if !unique.contains(&operator) {
+            unique.push(operator);
+        }
+    }
+    let flattened = unique;
+
+    let absorbed = |o: &Operator| match junction(o) {
+        Some((children, c)) if c != conjunction => children.iter().any(|c| flattened.contains(c)),
+        _ => false,
+    };
+    let mut operators = flattened
+        .iter()
+        .filter(|o| !absorbed(o))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    match operators.len() {
+        1 => operators.remove(0),
+        _ => junction_of(operators, conjunction),
+    }
+}
+
+/// Order of operators to sort them into a canonical form, by their types, their paths and then their operands.
+/// Only `equals` and `notEquals` are ordered by their values, so other leaves of the same type at the same path
+/// keep their relative order.
+fn compare(a: &Operator, b: &Operator) -> Ordering {
+    let (path_a, path_b) = (a.as_leaf().map(|l| l.path()), b.as_leaf().map(|l| l.path()));
+
+    rank(a)
+        .cmp(&rank(b))
+        .then_with(|| path_a.cmp(&path_b))
+        .then_with(|| match (a, b) {
+            (Operator::All(a), Operator::All(b)) => compare_each(&a.operators, &b.operators),
+            (Operator::Any(a), Operator::Any(b)) => compare_each(&a.operators, &b.operators),
+            (Operator::Not(a), Operator::Not(b)) => compare(&a.operator, &b.operator),
+            (Operator::Equals(a), Operator::Equals(b)) => compare_values(&a.to, &b.to),
+            (Operator::NotEquals(a), Operator::NotEquals(b)) => compare_values(&a.to, &b.to),
+            _ => Ordering::Equal,
+        })
+}
+
+/// Compares the operators lexicographically.
+fn compare_each(a: &[Operator], b: &[Operator]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| compare(a, b))
+        .find(|o| o.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+/// Position of the type of the operator in the canonical order, with leaves before the composite operators.
+fn rank(operator: &Operator) -> usize {
+    match operator {
+        Operator::Equals(_) => 0,
+        Operator::NotEquals(_) => 1,
+        Operator::InNetwork(_) => 2,
+        Operator::NotInNetwork(_) => 3,
+        Operator::Bucket(_) => 4,
+        #[cfg(feature = "chrono")]
+        Operator::Before(_) => 5,
+        #[cfg(feature = "chrono")]
+        Operator::After(_) => 6,
+        #[cfg(feature = "chrono")]
+        Operator::WithinLast(_) => 7,
+        #[cfg(feature = "chrono")]
+        Operator::DayOfWeek(_) => 8,
+        #[cfg(feature = "chrono")]
+        Operator::TimeOfDay(_) => 9,
+        #[cfg(feature = "semver")]
+        Operator::VersionCompare(_) => 10,
+        #[cfg(feature = "semver")]
+        Operator::VersionMatches(_) => 11,
+        Operator::Not(_) => 12,
+        Operator::All(_) => 13,
+        Operator::Any(_) => 14,
+    }
+}
+
+/// Order of values consistent with their equality, where a present optional is ordered as its value.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Optional(Some(a)), b) => compare_values(a, b),
+        (a, Value::Optional(Some(b))) => compare_values(a, b),
+        (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| compare_values(a, b))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::IpAddr(a), Value::IpAddr(b)) => a.cmp(b),
+        #[cfg(feature = "chrono")]
+        (Value::Timestamp(a), Value::Timestamp(b)) => a.cmp(b),
+        #[cfg(feature = "chrono")]
+        (Value::Duration(a), Value::Duration(b)) => a.cmp(b),
+        (a, b) => value_rank(a).cmp(&value_rank(b)),
+    }
+}
+
+fn value_rank(value: &Value) -> usize {
+    match value {
+        Value::Optional(_) => 0,
+        Value::Boolean(_) => 1,
+        Value::Integer(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::IpAddr(_) => 5,
+        #[cfg(feature = "chrono")]
+        Value::Timestamp(_) => 6,
+        #[cfg(feature = "chrono")]
+        Value::Duration(_) => 7,
+    }
+}
+
+/// Expands the normalized operator into clauses of leaves, which are `any` of `all` of them if `disjunctive`,
+/// otherwise `all` of `any` of them.
+fn clauses(operator: &Operator, disjunctive: bool) -> Vec<Vec<Operator>> {
+    match junction(operator) {
+        // The outer junction concatenates the clauses of the operators.
+        Some((operators, conjunction)) if conjunction != disjunctive => operators
+            .iter()
+            .flat_map(|o| clauses(o, disjunctive))
+            .collect(),
+        // The inner junction distributes over the outer one, taking the product of the clauses.
+        Some((operators, _)) => operators.iter().fold(vec![vec![]], |product, o| {
+            let clauses = clauses(o, disjunctive);
+            product
+                .iter()
+                .flat_map(|p| {
+                    clauses.iter().map(move |c| {
+                        let mut clause = p.clone();
+                        clause.extend(c.iter().cloned());
+                        clause
+                    })
+                })
+                .collect()
+        }),
+        _ => vec![vec![operator.clone()]],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decision::Decision;
+    use crate::environment::Environment;
+    use crate::operator::Operate;
+
+    fn parse(yaml: &str) -> Operator {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn normalize() {
+        // language=yaml
+        let yaml = r#"
+        type: all
+        operators:
+          - type: all
+            operators:
+              - { type: equals, where: b, to: 2 }
+              - { type: equals, where: a, to: 1 }
+          - type: any
+            operators:
+              - type: not
+                operator:
+                  type: not
+                  operator: { type: equals, where: a, to: 1 }
+          - type: not
+            operator:
+              type: any
+              operators:
+                - { type: equals, where: c, to: 3 }
+                - { type: bucket, where: id, to: 10 }
+          - type: any
+            operators:
+              - { type: equals, where: a, to: 1 }
+              - { type: equals, where: d, to: 4 }
+          - type: all
+            operators: []
+        "#;
+
+        // language=yaml
+        let expected = r#"
+        type: all
+        operators:
+          - { type: equals, where: a, to: 1 }
+          - { type: equals, where: b, to: 2 }
+          - { type: notEquals, where: c, to: 3 }
+          - type: not
+            operator: { type: bucket, where: id, to: 10 }
+        "#;
+
+        assert_eq!(parse(expected), super::normalize(&parse(yaml)));
+    }
+
+    #[test]
+    fn normalize_order() {
+        // language=yaml
+        let yaml = r#"
+        type: any
+        operators:
+          - { type: inNetwork, where: ip, networks: [10.0.0.0/8] }
+          - { type: equals, where: a, to: 2 }
+          - { type: inNetwork, where: ip, networks: [192.0.2.0/24] }
+          - { type: equals, where: a, to: [1, 2] }
+          - { type: inNetwork, where: ip, networks: [10.0.0.0/8] }
+          - { type: equals, where: a, to: 1 }
+        "#;
+
+        // language=yaml
+        let expected = r#"
+        type: any
+        operators:
+          - { type: equals, where: a, to: 1 }
+          - { type: equals, where: a, to: 2 }
+          - { type: equals, where: a, to: [1, 2] }
+          - { type: inNetwork, where: ip, networks: [10.0.0.0/8] }
+          - { type: inNetwork, where: ip, networks: [192.0.2.0/24] }
+        "#;
+
+        assert_eq!(parse(expected), super::normalize(&parse(yaml)));
+    }
+
+    #[test]
+    fn normalize_constants() {
+        // language=yaml
+        let yaml = r#"
+        type: any
+        operators:
+          - { type: equals, where: a, to: 1 }
+          - type: not
+            operator:
+              type: any
+              operators: []
+        "#;
+
+        assert_eq!(
+            parse("{ type: all, operators: [] }"),
+            super::normalize(&parse(yaml)),
+        );
+        assert_eq!(
+            parse("{ type: equals, where: a, to: 1 }"),
+            super::normalize(&parse(
+                "{ type: any, operators: [{ type: equals, where: a, to: 1 }, { type: any, operators: [] }] }"
+            )),
+        );
+    }
+
+    #[test]
+    fn normal_forms() {
+        // language=yaml
+        let yaml = r#"
+        type: all
+        operators:
+          - type: any
+            operators:
+              - { type: equals, where: a, to: 1 }
+              - { type: equals, where: b, to: 2 }
+          - { type: equals, where: c, to: 3 }
+        "#;
+
+        // language=yaml
+        let dnf = r#"
+        type: any
+        operators:
+          - type: all
+            operators:
+              - { type: equals, where: a, to: 1 }
+              - { type: equals, where: c, to: 3 }
+          - type: all
+            operators:
+              - { type: equals, where: b, to: 2 }
+              - { type: equals, where: c, to: 3 }
+        "#;
+
+        let operator = parse(yaml);
+        assert_eq!(parse(dnf), to_dnf(&operator));
+        assert_eq!(super::normalize(&operator), to_cnf(&operator));
+
+        let env = Environment::default();
+        for context in [vec![1, 0, 3], vec![0, 2, 0], vec![0, 0, 3]] {
+            let context = ["a", "b", "c"]
+                .into_iter()
+                .zip(context)
+                .collect::<std::collections::HashMap<_, _>>();
+            assert_eq!(
+                operator.operate(&context, &env).unwrap(),
+                to_dnf(&operator).operate(&context, &env).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_errors() {
+        // language=yaml
+        let yaml = r#"
+        type: all
+        operators:
+          - { type: equals, where: '1.0', to: 3 }
+          - { type: equals, where: '0.x', to: 1 }
+        "#;
+
+        let operator = parse(yaml);
+        let normalized = super::normalize(&operator);
+        let context = vec![vec![1], vec![2]];
+        let env = Environment::default();
+
+        // The rejected leaf is sorted after the erroring one, which is then evaluated first.
+        assert_eq!(Decision::Reject, operator.operate(&context, &env).unwrap(),);
+        assert!(normalized.operate(&context, &env).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::time::decide;
+use crate::value::{Value, ValueRef};
+
+/// Accepts if the timestamp at the path is strictly after the one written in RFC 3339.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct After {
+    pub r#where: String,
+    pub to: DateTime<FixedOffset>,
+}
+
+impl Operate for After {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison("after", &self.r#where, &Value::from(self.to))
+    }
+}
+
+impl Leaf for After {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        decide(actual, |t| t > self.to)
+    }
+}
+
+impl Display for After {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} after {}", self.r#where, self.to.to_rfc3339())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operate() {
+        let to = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap();
+        let after = After {
+            r#where: "0".to_string(),
+            to,
+        };
+
+        assert_eq!(
+            Decision::Accept,
+            after
+                .operate(
+                    &vec![to + chrono::Duration::seconds(1)],
+                    &Environment::default()
+                )
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            after.operate(&vec![to], &Environment::default()).unwrap()
+        );
+    }
+}
@@ -1,7 +1,11 @@
+use std::fmt::{Display, Formatter};
+
 use serde::{Deserialize, Serialize};
 
 use crate::context::Context;
 use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
 use crate::operator::{Error, Operate};
 use crate::Operator;
 
@@ -11,11 +15,67 @@ pub struct Any {
 }
 
 impl Operate for Any {
-    fn operate<C>(&self, context: &C) -> Result<Decision, Error>
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
     where
         C: Context,
     {
-        Decision::try_from_iter_any(self.operators.iter().map(|o| o.operate(context)))
+        Decision::try_from_iter_any(
+            self.operators
+                .iter()
+                .enumerate()
+                .map(|(i, o)| o.operate(context, env).map_err(|e| e.at(i))),
+        )
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        let mut decision = None;
+        let mut failed = false;
+        let mut children = Vec::with_capacity(self.operators.len());
+        for operator in &self.operators {
+            if failed {
+                children.push(operator.outline());
+                continue;
+            }
+            if decision == Some(Decision::Accept) {
+                children.push(operator.outline().short_circuited());
+                continue;
+            }
+
+            let explanation = operator.explain(context, env);
+            match explanation.decision {
+                Some(d) => decision = Some(decision.map_or(d, |c: Decision| c.or(d))),
+                // An error within the operator stops the evaluation, as `operate` does.
+                _ => failed = true,
+            }
+
+            children.push(explanation);
+        }
+
+        let explanation = Explanation::new("any").with_children(children);
+        match failed {
+            true => explanation,
+            false => explanation.with_decision(decision.unwrap_or(Decision::Reject)),
+        }
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::new("any").with_children(self.operators.iter().map(|o| o.outline()).collect())
+    }
+}
+
+impl Display for Any {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "any(")?;
+        for (i, operator) in self.operators.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", operator)?;
+        }
+        write!(f, ")")
     }
 }
 
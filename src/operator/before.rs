@@ -0,0 +1,96 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::time::decide;
+use crate::value::{Value, ValueRef};
+
+/// Accepts if the timestamp at the path is strictly before the one written in RFC 3339.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Before {
+    pub r#where: String,
+    pub to: DateTime<FixedOffset>,
+}
+
+impl Operate for Before {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison("before", &self.r#where, &Value::from(self.to))
+    }
+}
+
+impl Leaf for Before {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        decide(actual, |t| t < self.to)
+    }
+}
+
+impl Display for Before {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} before {}", self.r#where, self.to.to_rfc3339())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: '0'
+        to: 2024-01-01T00:00:00+09:00
+        "#;
+
+        let before = serde_yaml::from_str::<Before>(yaml).unwrap();
+
+        assert_eq!(
+            Decision::Accept,
+            before
+                .operate(&vec!["2023-12-31T14:59:59Z"], &Environment::default())
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            before
+                .operate(&vec!["2023-12-31T15:00:00Z"], &Environment::default())
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            before
+                .operate(&vec!["yesterday"], &Environment::default())
+                .unwrap()
+        );
+        assert_eq!(
+            Decision::Reject,
+            before
+                .operate(&Vec::<&str>::new(), &Environment::default())
+                .unwrap(),
+        );
+    }
+}
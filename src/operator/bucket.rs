@@ -0,0 +1,221 @@
+use std::borrow::{Borrow, Cow};
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::value::{Value, ValueRef};
+
+/// Assigns the value at the path to one of 100 buckets deterministically, accepting if the bucket is in the range.
+///
+/// The bucket is `fnv1a64(salt + ":" + key) % 100`, where `fnv1a64` is the 64-bit FNV-1a hash over the UTF-8 bytes,
+/// and `key` is the string itself for strings, or the decimal representation for integers.
+/// The assignment never changes across processes, platforms or versions; use a different salt to reshuffle.
+/// The range of the buckets is validated when deserialized, so that `from <= to <= 100`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "RawBucket")]
+pub struct Bucket {
+    pub r#where: String,
+    pub salt: String,
+
+    /// Lower bound of the buckets in percent, inclusive.
+    pub from: u8,
+
+    /// Upper bound of the buckets in percent, exclusive.
+    pub to: u8,
+}
+
+/// Bucket as written in rules, before the range is validated.
+#[derive(Deserialize)]
+struct RawBucket {
+    r#where: String,
+
+    #[serde(default)]
+    salt: String,
+
+    #[serde(default)]
+    from: u8,
+    to: u8,
+}
+
+impl TryFrom<RawBucket> for Bucket {
+    type Error = String;
+
+    fn try_from(raw: RawBucket) -> Result<Self, Self::Error> {
+        if raw.to > 100 {
+            return Err(format!("Upper bound of buckets exceeds 100: {}", raw.to));
+        }
+        if raw.from > raw.to {
+            return Err(format!(
+                "Lower bound of buckets exceeds the upper bound: [{}, {})",
+                raw.from, raw.to,
+            ));
+        }
+
+        Ok(Self {
+            r#where: raw.r#where,
+            salt: raw.salt,
+            from: raw.from,
+            to: raw.to,
+        })
+    }
+}
+
+impl Bucket {
+    /// Returns the bucket of the value, if it can be bucketed.
+    pub fn bucket_of(&self, value: &Value) -> Option<u8> {
+        self.bucket_of_ref(&ValueRef::from(value))
+    }
+
+    /// Returns the bucket of the value borrowed from the context, hashing strings without copying them.
+    fn bucket_of_ref(&self, value: &ValueRef) -> Option<u8> {
+        let key = match value {
+            ValueRef::String(s) => Cow::Borrowed(s.as_ref()),
+            ValueRef::Integer(i) => Cow::Owned(i.to_string()),
+            _ => return None,
+        };
+
+        let bytes = self.salt.bytes().chain(*b":").chain(key.bytes());
+        let hash = fnv1a64(bytes);
+
+        Some((hash % 100) as u8)
+    }
+
+    fn decide(&self, actual: &ValueRef) -> Decision {
+        self.bucket_of_ref(actual)
+            .map(|b| self.from <= b && b < self.to)
+            .unwrap_or(false)
+            .into()
+    }
+}
+
+/// 64-bit FNV-1a hash, which is simple enough to reproduce in any language.
+fn fnv1a64<I>(bytes: I) -> u64
+where
+    I: IntoIterator,
+    I::Item: Borrow<u8>,
+{
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.into_iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte.borrow() as u64).wrapping_mul(PRIME)
+    })
+}
+
+impl Operate for Bucket {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison(
+            "bucket",
+            &self.r#where,
+            &Value::from(vec![self.from, self.to]),
+        )
+    }
+}
+
+impl Leaf for Bucket {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        self.decide(actual)
+    }
+}
+
+impl Display for Bucket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in bucket [{}, {})", self.r#where, self.from, self.to)?;
+        match self.salt.is_empty() {
+            true => Ok(()),
+            false => write!(f, " salted with {}", self.salt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a64() {
+        assert_eq!(0xcbf29ce484222325, super::fnv1a64(b""));
+        assert_eq!(0xaf63dc4c8601ec8c, super::fnv1a64(b"a"));
+        assert_eq!(0x85944171f73967e8, super::fnv1a64(b"foobar"));
+    }
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: user.id
+        salt: new-checkout
+        to: 10
+        "#;
+
+        let bucket = serde_yaml::from_str::<Bucket>(yaml).unwrap();
+
+        // Buckets are pinned, so that a change in the hashing is caught.
+        assert_eq!(Some(6), bucket.bucket_of(&Value::from("alice")));
+        assert_eq!(Some(6), bucket.bucket_of(&Value::from(Some("alice"))));
+        assert_eq!(Some(48), bucket.bucket_of(&Value::from(42)));
+        assert_eq!(
+            bucket.bucket_of(&Value::from("42")),
+            bucket.bucket_of(&Value::from(42)),
+        );
+        assert_eq!(None, bucket.bucket_of(&Value::from(true)));
+
+        let accepted = (0..10000)
+            .filter(|i| bucket.decide(&ValueRef::from(i)) == Decision::Accept)
+            .count();
+        assert!((900..1100).contains(&accepted), "{}", accepted);
+        assert_eq!(Decision::Reject, bucket.decide(&ValueRef::Missing));
+    }
+
+    #[test]
+    fn deserialize_range() {
+        assert_eq!(
+            Bucket {
+                r#where: "user.id".to_string(),
+                salt: "".to_string(),
+                from: 0,
+                to: 100,
+            },
+            serde_yaml::from_str::<Bucket>("{ where: user.id, to: 100 }").unwrap(),
+        );
+        assert!(
+            serde_yaml::from_str::<Bucket>("{ where: user.id, to: 150 }")
+                .unwrap_err()
+                .to_string()
+                .contains("Upper bound of buckets exceeds 100: 150")
+        );
+        assert!(
+            serde_yaml::from_str::<Bucket>("{ where: user.id, from: 80, to: 20 }")
+                .unwrap_err()
+                .to_string()
+                .contains("Lower bound of buckets exceeds the upper bound: [80, 20)")
+        );
+        assert!(serde_yaml::from_str::<crate::Operator>(
+            "{ type: bucket, where: user.id, from: 80, to: 20 }"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{Datelike, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::time::{decide, is_utc};
+use crate::value::{Value, ValueRef};
+
+/// Accepts if the timestamp at the path falls on any of the days of the week in the timezone, UTC by default.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DayOfWeek {
+    pub r#where: String,
+    pub days: Vec<Weekday>,
+
+    #[serde(
+        default = "crate::time::utc",
+        skip_serializing_if = "crate::time::is_utc"
+    )]
+    pub timezone: Tz,
+}
+
+impl DayOfWeek {
+    fn decide(&self, actual: &ValueRef) -> Decision {
+        decide(actual, |t| {
+            self.days
+                .contains(&t.with_timezone(&self.timezone).weekday())
+        })
+    }
+}
+
+impl Operate for DayOfWeek {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        let days = self.days.iter().map(|d| d.to_string()).collect::<Value>();
+
+        Explanation::comparison("dayOfWeek", &self.r#where, &days)
+    }
+}
+
+impl Leaf for DayOfWeek {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        self.decide(actual)
+    }
+}
+
+impl Display for DayOfWeek {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let days = self.days.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+        write!(f, "{} on [{}]", self.r#where, days.join(", "))?;
+        match is_utc(&self.timezone) {
+            true => Ok(()),
+            false => write!(f, " in {}", self.timezone),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: '0'
+        days: [sat, Sunday]
+        timezone: Asia/Tokyo
+        "#;
+
+        let day_of_week = serde_yaml::from_str::<DayOfWeek>(yaml).unwrap();
+
+        assert_eq!(vec![Weekday::Sat, Weekday::Sun], day_of_week.days);
+        assert_eq!(
+            Decision::Accept,
+            day_of_week
+                .operate(&vec!["2024-01-05T15:00:00Z"], &Environment::default())
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            day_of_week
+                .operate(&vec!["2024-01-05T14:59:59Z"], &Environment::default())
+                .unwrap(),
+        );
+    }
+}
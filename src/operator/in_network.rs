@@ -0,0 +1,127 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::network::{to_ip_addr, NetworkSet};
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::value::{Value, ValueRef};
+
+/// Accepts if the IP address at the path is within any of the CIDR blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InNetwork {
+    pub r#where: String,
+    pub networks: NetworkSet,
+}
+
+/// Decides whether the IP address resolved from the context is within the networks.
+/// If it is not an IP address, the address is in neither the networks nor outside them.
+pub(crate) fn decide(actual: &ValueRef, networks: &NetworkSet, inside: bool) -> Decision {
+    to_ip_addr(actual)
+        .map(|a| networks.contains(a) == inside)
+        .unwrap_or(false)
+        .into()
+}
+
+/// Describes the networks as the value compared to.
+pub(crate) fn expected(networks: &NetworkSet) -> Value {
+    networks.blocks().iter().map(|b| b.to_string()).collect()
+}
+
+impl Operate for InNetwork {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison("inNetwork", &self.r#where, &expected(&self.networks))
+    }
+}
+
+impl Leaf for InNetwork {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        decide(actual, &self.networks, true)
+    }
+}
+
+impl Display for InNetwork {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in {}", self.r#where, expected(&self.networks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::IpAddr;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: client.ip
+        networks:
+          - 10.0.0.0/8
+          - 2001:db8::/32
+        "#;
+
+        let in_network = serde_yaml::from_str::<InNetwork>(yaml).unwrap();
+        let env = Environment::default();
+        let context = |ip: &str| {
+            std::collections::HashMap::from([(
+                "client",
+                std::collections::HashMap::from([("ip", ip.to_string())]),
+            )])
+        };
+
+        assert_eq!(
+            Decision::Accept,
+            in_network.operate(&context("10.1.2.3"), &env).unwrap(),
+        );
+        assert_eq!(
+            Decision::Accept,
+            in_network
+                .operate(&context("::ffff:10.0.0.1"), &env)
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            in_network.operate(&context("192.0.2.1"), &env).unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            in_network.operate(&context("localhost"), &env).unwrap(),
+        );
+
+        let typed = InNetwork {
+            r#where: "0".to_string(),
+            ..in_network.clone()
+        };
+        assert_eq!(
+            Decision::Accept,
+            typed
+                .operate(&vec!["2001:db8::1".parse::<IpAddr>().unwrap()], &env)
+                .unwrap(),
+        );
+        assert!(
+            serde_yaml::from_str::<InNetwork>("{ where: ip, networks: [10.0.0.0/33] }").is_err()
+        );
+    }
+}
@@ -1,28 +1,258 @@
+#[cfg(feature = "chrono")]
+mod after;
 mod all;
 mod any;
+#[cfg(feature = "chrono")]
+mod before;
+mod bucket;
+#[cfg(feature = "chrono")]
+mod day_of_week;
 mod equals;
+mod in_network;
+mod not;
 mod not_equals;
+mod not_in_network;
+#[cfg(feature = "chrono")]
+mod time_of_day;
+#[cfg(feature = "semver")]
+mod version_compare;
+#[cfg(feature = "semver")]
+mod version_matches;
+#[cfg(feature = "chrono")]
+mod within_last;
 
+#[cfg(feature = "chrono")]
+pub use after::*;
 pub use all::*;
 pub use any::*;
+#[cfg(feature = "chrono")]
+pub use before::*;
+pub use bucket::*;
+#[cfg(feature = "chrono")]
+pub use day_of_week::*;
 pub use equals::*;
+pub use in_network::*;
+pub use not::*;
 pub use not_equals::*;
+pub use not_in_network::*;
+#[cfg(feature = "chrono")]
+pub use time_of_day::*;
+#[cfg(feature = "semver")]
+pub use version_compare::*;
+#[cfg(feature = "semver")]
+pub use version_matches::*;
+#[cfg(feature = "chrono")]
+pub use within_last::*;
 
-use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize, Serializer};
 
 use crate::context::Context;
 use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::value::ValueRef;
+
+/// Location of an operator in the tree, as indices of the operators within `all` and `any`.
+/// The operator within `not` is at the index 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location(pub Vec<usize>);
+
+impl Location {
+    pub fn child(&self, index: usize) -> Self {
+        let mut indices = self.0.clone();
+        indices.push(index);
+        Self(indices)
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_root() {
+            return write!(f, "root");
+        }
+
+        for (i, index) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "operators[{}]", index)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for Location {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Unexpected context: {0}")]
     UnexpectedContext(#[from] crate::context::Error),
+
+    #[error("{source} (at {location})")]
+    Located {
+        location: Location,
+        #[source]
+        source: Box<Error>,
+    },
+
+    #[error("Error in rule {name}: {source}")]
+    InRule {
+        name: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Prepends the index of the operator within its parent to the location of the error.
+    pub fn at(self, index: usize) -> Self {
+        match self {
+            Self::Located {
+                mut location,
+                source,
+            } => {
+                location.0.insert(0, index);
+                Self::Located { location, source }
+            }
+            e => Self::Located {
+                location: Location(vec![index]),
+                source: Box::new(e),
+            },
+        }
+    }
+
+    /// Attaches the name of the rule that raised the error.
+    pub fn in_rule(self, name: &str) -> Self {
+        Self::InRule {
+            name: name.to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the name of the rule that raised the error, if attached.
+    pub fn rule(&self) -> Option<&str> {
+        match self {
+            Self::InRule { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the location of the operator that raised the error.
+    pub fn location(&self) -> Location {
+        match self {
+            Self::Located { location, .. } => location.clone(),
+            Self::InRule { source, .. } => source.location(),
+            _ => Location::default(),
+        }
+    }
+
+    /// Returns the underlying error, stripping the rule name and the location.
+    pub fn cause(&self) -> &Self {
+        match self {
+            Self::Located { source, .. } | Self::InRule { source, .. } => source.cause(),
+            e => e,
+        }
+    }
 }
 
+/// Operator evaluated against a context.
+/// The environment provides anything that does not come from the context, such as the current time.
 pub trait Operate {
-    fn operate<C>(&self, context: &C) -> Result<Decision, Error>
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
     where
         C: Context;
+
+    /// Evaluates the operator like [`Operate::operate`], tracing how the decision was made.
+    /// An error is recorded in the explanation of the operator raising it, instead of discarding the trace.
+    /// By default, the operator is explained as a single node with its decision or error.
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        let outline = self.outline();
+        match self.operate(context, env) {
+            Ok(d) => outline.with_decision(d),
+            Err(e) => outline.with_error(&e),
+        }
+    }
+
+    /// Describes the operator without evaluating it.
+    /// By default, the operator is described as a single node named after its type.
+    fn outline(&self) -> Explanation {
+        Explanation::new(std::any::type_name::<Self>())
+    }
+}
+
+/// Operator that decides on the value at a single path in the context, looking up nothing else.
+/// Lets a compiled plan resolve the value once and share it between the operators at the same path.
+pub(crate) trait Leaf {
+    fn path(&self) -> &str;
+
+    /// Decides on the value resolved from the context at the path, like [`Operate::operate`].
+    fn decide_on(&self, actual: &ValueRef, env: &Environment) -> Decision;
+}
+
+/// Treats a path which does not resolve in the context as an absent attribute rather than an error.
+/// Present values, including `None`, are kept to be compared.
+pub(crate) fn present<T>(
+    lookup: Result<T, crate::context::Error>,
+) -> Result<Option<T>, crate::context::Error> {
+    match lookup {
+        Ok(v) => Ok(Some(v)),
+        Err(crate::context::Error::UnexpectedPath(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Decides the leaf operator on the value looked up from the context at its path.
+/// If the attribute is absent in the context, the decision is indeterminate.
+pub(crate) fn operate_leaf<L, C>(
+    leaf: &L,
+    context: &C,
+    env: &Environment,
+) -> Result<Decision, Error>
+where
+    L: Leaf,
+    C: Context,
+{
+    Ok(
+        match present(context.get_ref_from_context_at(leaf.path()))? {
+            Some(actual) => leaf.decide_on(&actual, env),
+            _ => Decision::Indeterminate,
+        },
+    )
+}
+
+/// Explains the leaf operator with the value looked up from the context, or the error raised by the lookup.
+pub(crate) fn explain_leaf<L, C>(leaf: &L, context: &C, env: &Environment) -> Explanation
+where
+    L: Leaf + Operate,
+    C: Context,
+{
+    match present(context.get_ref_from_context_at(leaf.path())) {
+        Ok(Some(actual)) => {
+            let decision = leaf.decide_on(&actual, env);
+            leaf.outline()
+                .with_actual(actual.into())
+                .with_decision(decision)
+        }
+        Ok(None) => leaf.outline().with_decision(Decision::Indeterminate),
+        Err(e) => leaf.outline().with_error(&e.into()),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -30,20 +260,171 @@ pub trait Operate {
 pub enum Operator {
     All(All),
     Any(Any),
+    Not(Not),
     Equals(Equals),
     NotEquals(NotEquals),
+    InNetwork(InNetwork),
+    NotInNetwork(NotInNetwork),
+    Bucket(Bucket),
+    #[cfg(feature = "chrono")]
+    Before(Before),
+    #[cfg(feature = "chrono")]
+    After(After),
+    #[cfg(feature = "chrono")]
+    WithinLast(WithinLast),
+    #[cfg(feature = "chrono")]
+    DayOfWeek(DayOfWeek),
+    #[cfg(feature = "chrono")]
+    TimeOfDay(TimeOfDay),
+    #[cfg(feature = "semver")]
+    VersionCompare(VersionCompare),
+    #[cfg(feature = "semver")]
+    VersionMatches(VersionMatches),
+}
+
+impl Operator {
+    /// Returns the operator as a leaf, unless it is composed of other operators.
+    pub(crate) fn as_leaf(&self) -> Option<&dyn Leaf> {
+        match self {
+            Self::All(_) | Self::Any(_) | Self::Not(_) => None,
+            Self::Equals(o) => Some(o),
+            Self::NotEquals(o) => Some(o),
+            Self::InNetwork(o) => Some(o),
+            Self::NotInNetwork(o) => Some(o),
+            Self::Bucket(o) => Some(o),
+            #[cfg(feature = "chrono")]
+            Self::Before(o) => Some(o),
+            #[cfg(feature = "chrono")]
+            Self::After(o) => Some(o),
+            #[cfg(feature = "chrono")]
+            Self::WithinLast(o) => Some(o),
+            #[cfg(feature = "chrono")]
+            Self::DayOfWeek(o) => Some(o),
+            #[cfg(feature = "chrono")]
+            Self::TimeOfDay(o) => Some(o),
+            #[cfg(feature = "semver")]
+            Self::VersionCompare(o) => Some(o),
+            #[cfg(feature = "semver")]
+            Self::VersionMatches(o) => Some(o),
+        }
+    }
 }
 
 impl Operate for Operator {
-    fn operate<C>(&self, context: &C) -> Result<Decision, Error>
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        match self {
+            Self::All(o) => o.operate(context, env),
+            Self::Any(o) => o.operate(context, env),
+            Self::Not(o) => o.operate(context, env),
+            Self::Equals(o) => o.operate(context, env),
+            Self::NotEquals(o) => o.operate(context, env),
+            Self::InNetwork(o) => o.operate(context, env),
+            Self::NotInNetwork(o) => o.operate(context, env),
+            Self::Bucket(o) => o.operate(context, env),
+            #[cfg(feature = "chrono")]
+            Self::Before(o) => o.operate(context, env),
+            #[cfg(feature = "chrono")]
+            Self::After(o) => o.operate(context, env),
+            #[cfg(feature = "chrono")]
+            Self::WithinLast(o) => o.operate(context, env),
+            #[cfg(feature = "chrono")]
+            Self::DayOfWeek(o) => o.operate(context, env),
+            #[cfg(feature = "chrono")]
+            Self::TimeOfDay(o) => o.operate(context, env),
+            #[cfg(feature = "semver")]
+            Self::VersionCompare(o) => o.operate(context, env),
+            #[cfg(feature = "semver")]
+            Self::VersionMatches(o) => o.operate(context, env),
+        }
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
     where
         C: Context,
     {
         match self {
-            Self::All(o) => o.operate(context),
-            Self::Any(o) => o.operate(context),
-            Self::Equals(o) => o.operate(context),
-            Self::NotEquals(o) => o.operate(context),
+            Self::All(o) => o.explain(context, env),
+            Self::Any(o) => o.explain(context, env),
+            Self::Not(o) => o.explain(context, env),
+            Self::Equals(o) => o.explain(context, env),
+            Self::NotEquals(o) => o.explain(context, env),
+            Self::InNetwork(o) => o.explain(context, env),
+            Self::NotInNetwork(o) => o.explain(context, env),
+            Self::Bucket(o) => o.explain(context, env),
+            #[cfg(feature = "chrono")]
+            Self::Before(o) => o.explain(context, env),
+            #[cfg(feature = "chrono")]
+            Self::After(o) => o.explain(context, env),
+            #[cfg(feature = "chrono")]
+            Self::WithinLast(o) => o.explain(context, env),
+            #[cfg(feature = "chrono")]
+            Self::DayOfWeek(o) => o.explain(context, env),
+            #[cfg(feature = "chrono")]
+            Self::TimeOfDay(o) => o.explain(context, env),
+            #[cfg(feature = "semver")]
+            Self::VersionCompare(o) => o.explain(context, env),
+            #[cfg(feature = "semver")]
+            Self::VersionMatches(o) => o.explain(context, env),
+        }
+    }
+
+    fn outline(&self) -> Explanation {
+        match self {
+            Self::All(o) => o.outline(),
+            Self::Any(o) => o.outline(),
+            Self::Not(o) => o.outline(),
+            Self::Equals(o) => o.outline(),
+            Self::NotEquals(o) => o.outline(),
+            Self::InNetwork(o) => o.outline(),
+            Self::NotInNetwork(o) => o.outline(),
+            Self::Bucket(o) => o.outline(),
+            #[cfg(feature = "chrono")]
+            Self::Before(o) => o.outline(),
+            #[cfg(feature = "chrono")]
+            Self::After(o) => o.outline(),
+            #[cfg(feature = "chrono")]
+            Self::WithinLast(o) => o.outline(),
+            #[cfg(feature = "chrono")]
+            Self::DayOfWeek(o) => o.outline(),
+            #[cfg(feature = "chrono")]
+            Self::TimeOfDay(o) => o.outline(),
+            #[cfg(feature = "semver")]
+            Self::VersionCompare(o) => o.outline(),
+            #[cfg(feature = "semver")]
+            Self::VersionMatches(o) => o.outline(),
+        }
+    }
+}
+
+/// Describes the operator in a compact notation for humans, e.g. `all(region == eu, age != 0)`.
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All(o) => Display::fmt(o, f),
+            Self::Any(o) => Display::fmt(o, f),
+            Self::Not(o) => Display::fmt(o, f),
+            Self::Equals(o) => Display::fmt(o, f),
+            Self::NotEquals(o) => Display::fmt(o, f),
+            Self::InNetwork(o) => Display::fmt(o, f),
+            Self::NotInNetwork(o) => Display::fmt(o, f),
+            Self::Bucket(o) => Display::fmt(o, f),
+            #[cfg(feature = "chrono")]
+            Self::Before(o) => Display::fmt(o, f),
+            #[cfg(feature = "chrono")]
+            Self::After(o) => Display::fmt(o, f),
+            #[cfg(feature = "chrono")]
+            Self::WithinLast(o) => Display::fmt(o, f),
+            #[cfg(feature = "chrono")]
+            Self::DayOfWeek(o) => Display::fmt(o, f),
+            #[cfg(feature = "chrono")]
+            Self::TimeOfDay(o) => Display::fmt(o, f),
+            #[cfg(feature = "semver")]
+            Self::VersionCompare(o) => Display::fmt(o, f),
+            #[cfg(feature = "semver")]
+            Self::VersionMatches(o) => Display::fmt(o, f),
         }
     }
 }
@@ -0,0 +1,103 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{Error, Operate};
+use crate::Operator;
+
+/// Accepts if the operator rejects, and vice versa.
+/// `Indeterminate` and `NotApplicable` are left as they are.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Not {
+    pub operator: Box<Operator>,
+}
+
+impl Operate for Not {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        self.operator
+            .operate(context, env)
+            .map(|d| !d)
+            .map_err(|e| e.at(0))
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        let explanation = self.operator.explain(context, env);
+        let decision = explanation.decision.map(|d| !d);
+
+        let outline = Explanation::new("not").with_children(vec![explanation]);
+        match decision {
+            Some(d) => outline.with_decision(d),
+            _ => outline,
+        }
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::new("not").with_children(vec![self.operator.outline()])
+    }
+}
+
+impl Display for Not {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not({})", self.operator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        type: not
+        operator:
+          type: equals
+          where: "0"
+          to: 123
+        "#;
+
+        let operator = serde_yaml::from_str::<Operator>(yaml).unwrap();
+        let env = Environment::default();
+
+        assert_eq!(
+            Decision::Reject,
+            operator.operate(&vec![123], &env).unwrap(),
+        );
+        assert_eq!(
+            Decision::Accept,
+            operator.operate(&vec![456], &env).unwrap(),
+        );
+        assert_eq!(
+            Decision::Accept,
+            operator.operate(&vec![None::<i32>], &env).unwrap(),
+        );
+        assert_eq!(
+            Decision::Indeterminate,
+            operator.operate(&123, &env).unwrap(),
+        );
+        assert_eq!(
+            Explanation::new("not")
+                .with_children(vec![Explanation::comparison(
+                    "equals",
+                    "0",
+                    &Value::from(123)
+                )
+                .with_actual(Value::from(456))
+                .with_decision(Decision::Reject)])
+                .with_decision(Decision::Accept),
+            operator.explain(&vec![456], &env),
+        );
+    }
+}
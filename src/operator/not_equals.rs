@@ -1,9 +1,13 @@
+use std::fmt::{Display, Formatter};
+
 use serde::{Deserialize, Serialize};
 
 use crate::context::Context;
 use crate::decision::Decision;
-use crate::operator::{Error, Operate};
-use crate::value::Value;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::value::{Value, ValueRef};
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct NotEquals {
@@ -12,11 +16,38 @@ pub struct NotEquals {
 }
 
 impl Operate for NotEquals {
-    fn operate<C>(&self, context: &C) -> Result<Decision, Error>
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
     where
         C: Context,
     {
-        Ok((&context.get_from_context_at(&self.r#where)? != &self.to).into())
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison("notEquals", &self.r#where, &self.to)
+    }
+}
+
+impl Leaf for NotEquals {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        (actual != &self.to).into()
+    }
+}
+
+impl Display for NotEquals {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} != {}", self.r#where, self.to)
     }
 }
 
@@ -44,4 +75,38 @@ mod tests {
             operator,
         );
     }
+
+    #[test]
+    fn operate_missing() {
+        let context = vec![Some(123), None];
+        let not_equals = |r#where: &str, to: Value| NotEquals {
+            r#where: r#where.to_string(),
+            to,
+        };
+
+        assert_eq!(
+            Decision::Reject,
+            not_equals("0", Value::from(123))
+                .operate(&context, &Environment::default())
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Accept,
+            not_equals("1", Value::from(123))
+                .operate(&context, &Environment::default())
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Indeterminate,
+            not_equals("0.foo", Value::from(123))
+                .operate(&context, &Environment::default())
+                .unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            not_equals("1", Value::Optional(None))
+                .operate(&context, &Environment::default())
+                .unwrap(),
+        );
+    }
 }
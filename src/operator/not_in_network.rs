@@ -0,0 +1,90 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::network::NetworkSet;
+use crate::operator::in_network::{decide, expected};
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::value::ValueRef;
+
+/// Accepts if the IP address at the path is outside all of the CIDR blocks, e.g. a blocklist.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NotInNetwork {
+    pub r#where: String,
+    pub networks: NetworkSet,
+}
+
+impl Operate for NotInNetwork {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison("notInNetwork", &self.r#where, &expected(&self.networks))
+    }
+}
+
+impl Leaf for NotInNetwork {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        decide(actual, &self.networks, false)
+    }
+}
+
+impl Display for NotInNetwork {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} not in {}", self.r#where, expected(&self.networks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: '0'
+        networks: [192.0.2.0/24, 198.51.100.7]
+        "#;
+
+        let not_in_network = serde_yaml::from_str::<NotInNetwork>(yaml).unwrap();
+        let env = Environment::default();
+
+        for (ip, expected) in [
+            ("203.0.113.1", Decision::Accept),
+            ("192.0.2.200", Decision::Reject),
+            ("198.51.100.7", Decision::Reject),
+            ("198.51.100.8", Decision::Accept),
+            ("not an address", Decision::Reject),
+        ] {
+            assert_eq!(expected, not_in_network.operate(&vec![ip], &env).unwrap());
+        }
+        assert_eq!(
+            Decision::Reject,
+            not_in_network.operate(&vec![None::<String>], &env).unwrap(),
+        );
+        assert_eq!(
+            Decision::Indeterminate,
+            not_in_network.operate(&"203.0.113.1", &env).unwrap(),
+        );
+    }
+}
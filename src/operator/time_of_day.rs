@@ -0,0 +1,136 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::time::{decide, format_time_of_day, is_utc};
+use crate::value::{Value, ValueRef};
+
+/// Accepts if the time of day of the timestamp at the path is within `from` (inclusive) and `to` (exclusive),
+/// in the timezone, UTC by default. The range wraps around midnight if `from` is later than `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TimeOfDay {
+    pub r#where: String,
+
+    #[serde(with = "crate::time::time_of_day")]
+    pub from: NaiveTime,
+
+    #[serde(with = "crate::time::time_of_day")]
+    pub to: NaiveTime,
+
+    #[serde(
+        default = "crate::time::utc",
+        skip_serializing_if = "crate::time::is_utc"
+    )]
+    pub timezone: Tz,
+}
+
+impl TimeOfDay {
+    fn decide(&self, actual: &ValueRef) -> Decision {
+        decide(actual, |t| {
+            let time = t.with_timezone(&self.timezone).time();
+            match self.from <= self.to {
+                true => self.from <= time && time < self.to,
+                false => self.from <= time || time < self.to,
+            }
+        })
+    }
+}
+
+impl Operate for TimeOfDay {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        let range = Value::from(vec![
+            format_time_of_day(&self.from),
+            format_time_of_day(&self.to),
+        ]);
+
+        Explanation::comparison("timeOfDay", &self.r#where, &range)
+    }
+}
+
+impl Leaf for TimeOfDay {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        self.decide(actual)
+    }
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} between {} and {}",
+            self.r#where,
+            format_time_of_day(&self.from),
+            format_time_of_day(&self.to),
+        )?;
+        match is_utc(&self.timezone) {
+            true => Ok(()),
+            false => write!(f, " in {}", self.timezone),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: '0'
+        from: '09:00'
+        to: '17:00'
+        timezone: Asia/Tokyo
+        "#;
+
+        let business_hours = serde_yaml::from_str::<TimeOfDay>(yaml).unwrap();
+        let night = TimeOfDay {
+            from: business_hours.to,
+            to: business_hours.from,
+            ..business_hours.clone()
+        };
+
+        for (t, expected) in [
+            ("2024-01-01T00:00:00Z", Decision::Accept),
+            ("2024-01-01T07:59:59Z", Decision::Accept),
+            ("2024-01-01T08:00:00Z", Decision::Reject),
+            ("2023-12-31T23:59:59Z", Decision::Reject),
+        ] {
+            assert_eq!(
+                expected,
+                business_hours
+                    .operate(&vec![t], &Environment::default())
+                    .unwrap()
+            );
+            assert_ne!(
+                expected,
+                night.operate(&vec![t], &Environment::default()).unwrap()
+            );
+        }
+    }
+}
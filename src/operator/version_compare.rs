@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::value::{Value, ValueRef};
+use crate::version::decide;
+
+/// Comparison between two versions, in the order of semantic versioning.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Comparison {
+    Lt,
+    Lte,
+    Eq,
+    Ne,
+    Gte,
+    Gt,
+}
+
+impl Comparison {
+    pub fn matches(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Lt => ordering.is_lt(),
+            Self::Lte => ordering.is_le(),
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Gte => ordering.is_ge(),
+            Self::Gt => ordering.is_gt(),
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lt => write!(f, "<"),
+            Self::Lte => write!(f, "<="),
+            Self::Eq => write!(f, "=="),
+            Self::Ne => write!(f, "!="),
+            Self::Gte => write!(f, ">="),
+            Self::Gt => write!(f, ">"),
+        }
+    }
+}
+
+/// Compares the semantic version at the path to the literal one, e.g. `3.10.0` is greater than `3.9.0`.
+/// Versions are compared by their precedence, so build metadata is ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct VersionCompare {
+    pub r#where: String,
+    pub comparison: Comparison,
+    pub to: Version,
+}
+
+impl VersionCompare {
+    fn decide(&self, actual: &ValueRef) -> Decision {
+        decide(actual, |v| {
+            self.comparison.matches(v.cmp_precedence(&self.to))
+        })
+    }
+}
+
+impl Operate for VersionCompare {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison(
+            "versionCompare",
+            &self.r#where,
+            &Value::from(self.to.to_string()),
+        )
+    }
+}
+
+impl Leaf for VersionCompare {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        self.decide(actual)
+    }
+}
+
+impl Display for VersionCompare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.r#where, self.comparison, self.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: '0'
+        comparison: gte
+        to: 3.9.0
+        "#;
+
+        let version_compare = serde_yaml::from_str::<VersionCompare>(yaml).unwrap();
+        let env = Environment::default();
+
+        for (v, expected) in [
+            ("3.10.0", Decision::Accept),
+            ("3.9.0", Decision::Accept),
+            ("3.9.0-beta.1", Decision::Reject),
+            ("3.8.12", Decision::Reject),
+            ("3.10", Decision::Reject),
+        ] {
+            assert_eq!(expected, version_compare.operate(&vec![v], &env).unwrap());
+        }
+
+        // language=yaml
+        let yaml = r#"
+        where: '0'
+        comparison: eq
+        to: 3.9.0+build.1
+        "#;
+
+        let version_compare = serde_yaml::from_str::<VersionCompare>(yaml).unwrap();
+
+        for (v, expected) in [
+            ("3.9.0", Decision::Accept),
+            ("3.9.0+build.2", Decision::Accept),
+            ("3.9.0-beta.1+build.1", Decision::Reject),
+        ] {
+            assert_eq!(expected, version_compare.operate(&vec![v], &env).unwrap());
+        }
+
+        assert!(serde_yaml::from_str::<VersionCompare>(
+            "{ where: '0', comparison: gt, to: '3.9' }"
+        )
+        .is_err());
+    }
+}
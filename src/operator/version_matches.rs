@@ -0,0 +1,100 @@
+use std::fmt::{Display, Formatter};
+
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::value::{Value, ValueRef};
+use crate::version::decide;
+
+/// Accepts if the semantic version at the path satisfies the requirement, e.g. `^3.2` or `>=3.2.0, <4`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct VersionMatches {
+    pub r#where: String,
+    pub requirement: VersionReq,
+}
+
+impl VersionMatches {
+    fn decide(&self, actual: &ValueRef) -> Decision {
+        decide(actual, |v| self.requirement.matches(&v))
+    }
+}
+
+impl Operate for VersionMatches {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison(
+            "versionMatches",
+            &self.r#where,
+            &Value::from(self.requirement.to_string()),
+        )
+    }
+}
+
+impl Leaf for VersionMatches {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, _: &Environment) -> Decision {
+        self.decide(actual)
+    }
+}
+
+impl Display for VersionMatches {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} matches {}", self.r#where, self.requirement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: app.version
+        requirement: ^3.2
+        "#;
+
+        let version_matches = serde_yaml::from_str::<VersionMatches>(yaml).unwrap();
+        let env = Environment::default();
+        let context = |v: &str| {
+            std::collections::HashMap::from([(
+                "app",
+                std::collections::HashMap::from([("version", v.to_string())]),
+            )])
+        };
+
+        for (v, expected) in [
+            ("3.2.0", Decision::Accept),
+            ("3.10.1", Decision::Accept),
+            ("3.1.9", Decision::Reject),
+            ("4.0.0", Decision::Reject),
+        ] {
+            assert_eq!(
+                expected,
+                version_matches.operate(&context(v), &env).unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::explanation::Explanation;
+use crate::operator::{explain_leaf, operate_leaf, Error, Leaf, Operate};
+use crate::time::{decide, format_duration};
+use crate::value::{Value, ValueRef};
+
+/// Accepts if the timestamp at the path is within the duration up to now, e.g. `7d` or `1h30m`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WithinLast {
+    pub r#where: String,
+
+    #[serde(with = "crate::time::duration")]
+    pub duration: Duration,
+}
+
+impl WithinLast {
+    fn decide(&self, actual: &ValueRef, env: &Environment) -> Decision {
+        let now = DateTime::<Utc>::from(env.now());
+
+        decide(actual, |t| {
+            t <= now && now.fixed_offset() - t <= self.duration
+        })
+    }
+}
+
+impl Operate for WithinLast {
+    fn operate<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        operate_leaf(self, context, env)
+    }
+
+    fn explain<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        explain_leaf(self, context, env)
+    }
+
+    fn outline(&self) -> Explanation {
+        Explanation::comparison("withinLast", &self.r#where, &Value::from(self.duration))
+    }
+}
+
+impl Leaf for WithinLast {
+    fn path(&self) -> &str {
+        &self.r#where
+    }
+
+    fn decide_on(&self, actual: &ValueRef, env: &Environment) -> Decision {
+        self.decide(actual, env)
+    }
+}
+
+impl Display for WithinLast {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} within last {}",
+            self.r#where,
+            format_duration(&self.duration),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::FixedClock;
+
+    #[test]
+    fn operate() {
+        // language=yaml
+        let yaml = r#"
+        where: '0'
+        duration: 7d
+        "#;
+
+        let within_last = serde_yaml::from_str::<WithinLast>(yaml).unwrap();
+        let clock = FixedClock(
+            DateTime::parse_from_rfc3339("2024-01-08T00:00:00Z")
+                .unwrap()
+                .into(),
+        );
+        let env = Environment::default().with_clock(&clock);
+
+        assert_eq!(Duration::days(7), within_last.duration);
+        for (t, expected) in [
+            ("2024-01-01T00:00:00Z", Decision::Accept),
+            ("2023-12-31T23:59:59Z", Decision::Reject),
+            ("2024-01-08T00:00:00Z", Decision::Accept),
+            ("2024-01-08T00:00:01Z", Decision::Reject),
+        ] {
+            assert_eq!(expected, within_last.operate(&vec![t], &env).unwrap());
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! Rules compiled into a plan for hot paths, which evaluates the rules against many contexts with less work.
+//!
+//! Paths are split once at compile time, and the operators at the same path share the value borrowed from the context.
+//! All paths are resolved in a single walk at the start of each evaluation with [`Context::get_refs_from_context`],
+//! where paths sharing a prefix share its lookup, e.g. `user.role` and `user.banned` descend into `user` once
+//! for derived contexts. Lookups that fail are repeated when an operator needs the value, so errors are raised
+//! only where the rules raise them. Identical leaf operators across all rules are deduplicated,
+//! so each of them is decided at most once per evaluation.
+
+use std::collections::HashMap;
+
+use crate::combining::Combining;
+use crate::context::Context;
+use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::evaluation::{ErrorPolicy, Evaluation};
+use crate::operator::{present, Error, Leaf, Operator};
+use crate::rule::{Rule, Rules};
+use crate::value::ValueRef;
+
+/// Operator compiled into indices of the deduplicated leaves.
+enum Node {
+    All(Vec<Node>),
+    Any(Vec<Node>),
+    Not(Box<Node>),
+    Leaf(usize),
+}
+
+struct CompiledRule<'a> {
+    name: &'a str,
+    rule: &'a Rule,
+    target: Option<Node>,
+    operator: Node,
+}
+
+/// Values borrowed from the context and decisions in an evaluation, indexed by the paths and the leaves respectively.
+/// A resolved value is `None` if the path does not resolve in the context, and is not cached if the lookup failed.
+struct Cache<'c> {
+    values: Vec<Option<Option<ValueRef<'c>>>>,
+    decisions: Vec<Option<Decision>>,
+}
+
+/// Rules compiled for faster evaluation, making the same decisions as the rules.
+pub struct Plan<'a> {
+    /// Segments of the distinct paths.
+    paths: Vec<Vec<&'a str>>,
+
+    /// Distinct leaf operators with the indices of their paths.
+    leaves: Vec<(&'a dyn Leaf, usize)>,
+    rules: Vec<CompiledRule<'a>>,
+}
+
+impl<'a> Plan<'a> {
+    /// Compiles the rules into a plan.
+    pub fn new(rules: &'a Rules) -> Self {
+        let mut compiler = Compiler::default();
+        let rules = rules
+            .iter()
+            .map(|(name, rule)| CompiledRule {
+                name,
+                rule,
+                target: rule.target().map(|t| compiler.compile(t)),
+                operator: compiler.compile(rule.operator()),
+            })
+            .collect();
+
+        Self {
+            paths: compiler.paths,
+            leaves: compiler.leaves,
+            rules,
+        }
+    }
+
+    /// Number of the distinct paths looked up by the rules.
+    pub fn paths(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Number of the distinct leaf operators in the rules.
+    pub fn leaves(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Evaluate all rules against the context, like [`Rules::evaluate`].
+    pub fn evaluate<C>(&self, context: &C, policy: ErrorPolicy) -> Result<Evaluation<'a>, Error>
+    where
+        C: Context,
+    {
+        self.evaluate_in(context, &Environment::SYSTEM, policy)
+    }
+
+    /// Evaluate all rules against the context in the environment, like [`Rules::evaluate_in`].
+    pub fn evaluate_in<C>(
+        &self,
+        context: &C,
+        env: &Environment,
+        policy: ErrorPolicy,
+    ) -> Result<Evaluation<'a>, Error>
+    where
+        C: Context,
+    {
+        let mut cache = self.cache(context);
+        let mut evaluation = Evaluation::default();
+        for rule in &self.rules {
+            match self.decide_rule(rule, context, env, &mut cache) {
+                Ok(Decision::Accept) => evaluation.matched.push((rule.name, rule.rule)),
+                Ok(_) => {}
+                Err(e) => match policy {
+                    ErrorPolicy::FailFast => return Err(e),
+                    ErrorPolicy::RejectOnError => {}
+                    ErrorPolicy::Collect => evaluation.errors.push((rule.name, e)),
+                },
+            }
+        }
+
+        Ok(evaluation)
+    }
+
+    /// Fold the effects of the rules into a final decision with the combining algorithm, like [`Rules::decide`].
+    pub fn decide<C>(&self, context: &C, combining: Combining) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        self.decide_in(context, &Environment::SYSTEM, combining)
+    }
+
+    /// Fold the effects of the rules into a final decision in the environment, like [`Rules::decide_in`].
+    pub fn decide_in<C>(
+        &self,
+        context: &C,
+        env: &Environment,
+        combining: Combining,
+    ) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        let mut cache = self.cache(context);
+        combining.try_combine(self.rules.iter().map(|rule| {
+            self.decide_rule(rule, context, env, &mut cache)
+                .map(|d| rule.rule.effect().apply(d))
+        }))
+    }
+
+    fn cache<'c, C>(&self, context: &'c C) -> Cache<'c>
+    where
+        C: Context,
+    {
+        let paths = self.paths.iter().map(|p| p.as_slice()).collect::<Vec<_>>();
+        Cache {
+            values: context
+                .get_refs_from_context(&paths)
+                .into_iter()
+                .map(|v| present(v).ok())
+                .collect(),
+            decisions: vec![None; self.leaves.len()],
+        }
+    }
+
+    /// Decides the rule like [`Rule::is_satisfied_by_in`], attaching the name of the rule to errors.
+    fn decide_rule<'c, C>(
+        &self,
+        rule: &CompiledRule,
+        context: &'c C,
+        env: &Environment,
+        cache: &mut Cache<'c>,
+    ) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        let decide = |cache: &mut Cache<'c>| {
+            if let Some(target) = &rule.target {
+                match self.decide_node(target, context, env, cache)? {
+                    Decision::Accept => {}
+                    Decision::Indeterminate => return Ok(Decision::Indeterminate),
+                    _ => return Ok(Decision::NotApplicable),
+                }
+            }
+
+            self.decide_node(&rule.operator, context, env, cache)
+        };
+
+        decide(cache).map_err(|e| e.in_rule(rule.name))
+    }
+
+    fn decide_node<'c, C>(
+        &self,
+        node: &Node,
+        context: &'c C,
+        env: &Environment,
+        cache: &mut Cache<'c>,
+    ) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        match node {
+            Node::All(nodes) => {
+                Decision::try_from_iter_all(nodes.iter().enumerate().map(|(i, n)| {
+                    self.decide_node(n, context, env, cache)
+                        .map_err(|e| e.at(i))
+                }))
+            }
+            Node::Any(nodes) => {
+                Decision::try_from_iter_any(nodes.iter().enumerate().map(|(i, n)| {
+                    self.decide_node(n, context, env, cache)
+                        .map_err(|e| e.at(i))
+                }))
+            }
+            Node::Not(node) => self
+                .decide_node(node, context, env, cache)
+                .map(|d| !d)
+                .map_err(|e| e.at(0)),
+            Node::Leaf(index) => {
+                if let Some(d) = cache.decisions[*index] {
+                    return Ok(d);
+                }
+
+                let (leaf, path) = self.leaves[*index];
+                let value = match cache.values[path].take() {
+                    Some(v) => v,
+                    _ => present(context.get_ref_from_context(self.paths[path].iter().copied()))?,
+                };
+
+                let decision = match &value {
+                    Some(v) => leaf.decide_on(v, env),
+                    _ => Decision::Indeterminate,
+                };
+                cache.values[path] = Some(value);
+                cache.decisions[*index] = Some(decision);
+
+                Ok(decision)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Compiler<'a> {
+    paths: Vec<Vec<&'a str>>,
+    path_indices: HashMap<&'a str, usize>,
+    leaves: Vec<(&'a dyn Leaf, usize)>,
+
+    /// Leaf operators with their indices for each path, to find identical ones.
+    leaves_by_path: Vec<Vec<(&'a Operator, usize)>>,
+}
+
+impl<'a> Compiler<'a> {
+    fn compile(&mut self, operator: &'a Operator) -> Node {
+        let leaf = match (operator, operator.as_leaf()) {
+            (Operator::All(o), _) => {
+                return Node::All(o.operators.iter().map(|o| self.compile(o)).collect());
+            }
+            (Operator::Any(o), _) => {
+                return Node::Any(o.operators.iter().map(|o| self.compile(o)).collect());
+            }
+            (Operator::Not(o), _) => return Node::Not(Box::new(self.compile(&o.operator))),
+            (_, Some(leaf)) => leaf,
+            _ => unreachable!("operators other than all, any and not are leaves"),
+        };
+
+        let path = match self.path_indices.get(leaf.path()) {
+            Some(p) => *p,
+            _ => {
+                self.paths.push(leaf.path().split('.').collect());
+                self.leaves_by_path.push(vec![]);
+                self.path_indices.insert(leaf.path(), self.paths.len() - 1);
+                self.paths.len() - 1
+            }
+        };
+
+        let index = match self.leaves_by_path[path]
+            .iter()
+            .find(|(o, _)| *o == operator)
+        {
+            Some((_, i)) => *i,
+            _ => {
+                self.leaves.push((leaf, path));
+                self.leaves_by_path[path].push((operator, self.leaves.len() - 1));
+                self.leaves.len() - 1
+            }
+        };
+
+        Node::Leaf(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate as kimari;
+    use crate::schema::Schema;
+    use crate::value::Value;
+
+    // language=yaml
+    const RULES: &str = r#"
+    admin:
+      type: all
+      operators:
+        - { type: equals, where: user.role, to: admin }
+        - { type: notEquals, where: user.banned, to: true }
+    eu_admin:
+      target: { type: equals, where: user.role, to: admin }
+      type: any
+      operators:
+        - { type: equals, where: region, to: eu }
+        - type: not
+          operator: { type: equals, where: region, to: us }
+    unknown:
+      type: equals
+      where: unknown
+      to: 1
+    tagged_admin:
+      target: { type: equals, where: user.role, to: admin }
+      type: equals
+      where: tags.first
+      to: beta
+    "#;
+
+    #[derive(kimari::Context)]
+    struct User {
+        role: String,
+        banned: bool,
+    }
+
+    #[derive(kimari::Context)]
+    struct Request {
+        user: User,
+        region: String,
+        tags: Vec<String>,
+    }
+
+    /// Context counting the lookups made to it.
+    struct Counting<'a> {
+        request: &'a Request,
+        lookups: &'a Cell<usize>,
+    }
+
+    impl Context for Counting<'_> {
+        fn get_from_context<'a, I>(&self, path: I) -> Result<Value, kimari::context::Error>
+        where
+            I: IntoIterator<Item = &'a str>,
+        {
+            self.lookups.set(self.lookups.get() + 1);
+            self.request.get_from_context(path)
+        }
+
+        fn schema() -> Schema {
+            Request::schema()
+        }
+    }
+
+    fn request(role: &str, region: &str) -> Request {
+        Request {
+            user: User {
+                role: role.to_string(),
+                banned: false,
+            },
+            region: region.to_string(),
+            tags: vec!["beta".to_string()],
+        }
+    }
+
+    #[test]
+    fn evaluate() {
+        let rules = serde_yaml::from_str::<Rules>(RULES).unwrap();
+        let plan = Plan::new(&rules);
+
+        assert_eq!(5, plan.paths());
+        assert_eq!(6, plan.leaves());
+
+        for request in [
+            request("admin", "eu"),
+            request("admin", "us"),
+            request("user", "eu"),
+        ] {
+            let lookups = Cell::new(0);
+            let context = Counting {
+                request: &request,
+                lookups: &lookups,
+            };
+
+            let expected = rules.evaluate(&request, ErrorPolicy::Collect).unwrap();
+            let actual = plan.evaluate(&context, ErrorPolicy::Collect).unwrap();
+
+            assert_eq!(expected.matched, actual.matched);
+            assert_eq!(
+                expected
+                    .errors
+                    .iter()
+                    .map(|(n, e)| (*n, e.to_string()))
+                    .collect::<Vec<_>>(),
+                actual
+                    .errors
+                    .iter()
+                    .map(|(n, e)| (*n, e.to_string()))
+                    .collect::<Vec<_>>(),
+            );
+            // Failed lookups are repeated where the rules raise their errors.
+            assert!(lookups.get() <= plan.paths() + actual.errors.len());
+
+            for combining in [Combining::DenyOverrides, Combining::FirstApplicable] {
+                assert_eq!(
+                    rules.decide(&request, combining).map_err(|e| e.to_string()),
+                    plan.decide(&request, combining).map_err(|e| e.to_string()),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_errors() {
+        let rules = serde_yaml::from_str::<Rules>(RULES).unwrap();
+        let plan = Plan::new(&rules);
+
+        // The lookup of `tags.first` fails, which only the rules for admins reach.
+        let mut decisions = vec![];
+        for request in [request("admin", "eu"), request("user", "eu")] {
+            let expected = rules.evaluate(&request, ErrorPolicy::FailFast);
+            let actual = plan.evaluate(&request, ErrorPolicy::FailFast);
+            assert_eq!(
+                expected.map(|e| e.matched).map_err(|e| e.to_string()),
+                actual.map(|e| e.matched).map_err(|e| e.to_string()),
+            );
+
+            for combining in [Combining::DenyOverrides, Combining::PermitOverrides] {
+                let expected = rules.decide(&request, combining).map_err(|e| e.to_string());
+                assert_eq!(
+                    expected,
+                    plan.decide(&request, combining).map_err(|e| e.to_string()),
+                );
+                decisions.push(expected);
+            }
+        }
+
+        assert!(decisions.iter().any(|d| d.is_err()));
+        assert!(decisions.iter().any(|d| d.is_ok()));
+    }
+
+    /// User counting the lookups descending into it.
+    struct CountingUser<'a> {
+        user: &'a User,
+        lookups: &'a Cell<usize>,
+    }
+
+    impl Context for CountingUser<'_> {
+        fn get_from_context<'a, I>(&self, path: I) -> Result<Value, kimari::context::Error>
+        where
+            I: IntoIterator<Item = &'a str>,
+        {
+            self.lookups.set(self.lookups.get() + 1);
+            self.user.get_from_context(path)
+        }
+
+        fn get_refs_from_context(
+            &self,
+            paths: &[&[&str]],
+        ) -> Vec<Result<ValueRef<'_>, kimari::context::Error>> {
+            self.lookups.set(self.lookups.get() + 1);
+            self.user.get_refs_from_context(paths)
+        }
+    }
+
+    #[derive(kimari::Context)]
+    struct SharedRequest<'a> {
+        user: CountingUser<'a>,
+        region: String,
+    }
+
+    #[test]
+    fn evaluate_shared_prefix() {
+        let rules = serde_yaml::from_str::<Rules>(RULES).unwrap();
+        let plan = Plan::new(&rules);
+
+        let user = User {
+            role: "admin".to_string(),
+            banned: false,
+        };
+        let lookups = Cell::new(0);
+        let context = SharedRequest {
+            user: CountingUser {
+                user: &user,
+                lookups: &lookups,
+            },
+            region: "eu".to_string(),
+        };
+
+        let expected = rules.evaluate(&context, ErrorPolicy::Collect).unwrap();
+        assert!(lookups.get() > 1);
+
+        lookups.set(0);
+        let actual = plan.evaluate(&context, ErrorPolicy::Collect).unwrap();
+        assert_eq!(expected.matched, actual.matched);
+
+        // `user.role` and `user.banned` descend into `user` once.
+        assert_eq!(1, lookups.get());
+    }
+}
@@ -1,68 +1,319 @@
-use std::collections::BTreeMap;
 use std::ops::{Deref, DerefMut};
 
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+use crate::combining::{Combining, Effect};
 use crate::context::Context;
 use crate::decision::Decision;
+use crate::environment::Environment;
+use crate::evaluation::{ErrorPolicy, Evaluation};
+use crate::explanation::Explanation;
 use crate::operator::{Error, Operate, Operator};
+use crate::plan::Plan;
+use crate::schema::ValidationError;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Rule {
+    /// Operator that determines whether the rule applies to the context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<Operator>,
+
+    /// Effect of the rule when it is satisfied, used by combining algorithms.
+    #[serde(default, skip_serializing_if = "Effect::is_permit")]
+    effect: Effect,
+
     #[serde(flatten)]
     operator: Operator,
 }
 
 impl Rule {
+    pub fn new(operator: Operator) -> Self {
+        Self {
+            target: None,
+            effect: Effect::default(),
+            operator,
+        }
+    }
+
+    /// Sets the target of the rule.
+    /// If the target is not accepted, the rule is not applicable to the context.
+    pub fn with_target(self, target: Operator) -> Self {
+        Self {
+            target: Some(target),
+            ..self
+        }
+    }
+
+    /// Sets the effect of the rule.
+    pub fn with_effect(self, effect: Effect) -> Self {
+        Self { effect, ..self }
+    }
+
+    pub fn effect(&self) -> Effect {
+        self.effect
+    }
+
+    pub fn target(&self) -> Option<&Operator> {
+        self.target.as_ref()
+    }
+
+    pub fn operator(&self) -> &Operator {
+        &self.operator
+    }
+
+    /// Describes the rule without evaluating it, in the same structure as [`Rule::explain`].
+    pub fn outline(&self) -> Explanation {
+        match &self.target {
+            Some(t) => {
+                Explanation::new("rule").with_children(vec![t.outline(), self.operator.outline()])
+            }
+            _ => self.operator.outline(),
+        }
+    }
+
     /// Determine whether the context satisfies the rule or not.
     pub fn is_satisfied_by<C>(&self, context: &C) -> Result<Decision, Error>
     where
         C: Context,
     {
-        self.operator.operate(context)
+        self.is_satisfied_by_in(context, &Environment::SYSTEM)
+    }
+
+    /// Determine whether the context satisfies the rule or not, in the environment.
+    pub fn is_satisfied_by_in<C>(&self, context: &C, env: &Environment) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        if let Some(target) = &self.target {
+            match target.operate(context, env)? {
+                Decision::Accept => {}
+                Decision::Indeterminate => return Ok(Decision::Indeterminate),
+                _ => return Ok(Decision::NotApplicable),
+            }
+        }
+
+        self.operator.operate(context, env)
+    }
+
+    /// Determine whether the context satisfies the rule or not, tracing how the decision was made.
+    /// An error is recorded in the explanation of the operator raising it.
+    pub fn explain<C>(&self, context: &C) -> Explanation
+    where
+        C: Context,
+    {
+        self.explain_in(context, &Environment::SYSTEM)
+    }
+
+    /// Determine whether the context satisfies the rule or not in the environment, tracing how the decision was made.
+    pub fn explain_in<C>(&self, context: &C, env: &Environment) -> Explanation
+    where
+        C: Context,
+    {
+        let target = match &self.target {
+            Some(t) => t.explain(context, env),
+            _ => return self.operator.explain(context, env),
+        };
+
+        let (decision, operator) = match target.decision {
+            Some(Decision::Accept) => {
+                let operator = self.operator.explain(context, env);
+                (operator.decision, operator)
+            }
+            None => (None, self.operator.outline()),
+            Some(Decision::Indeterminate) => (
+                Some(Decision::Indeterminate),
+                self.operator.outline().short_circuited(),
+            ),
+            _ => (
+                Some(Decision::NotApplicable),
+                self.operator.outline().short_circuited(),
+            ),
+        };
+
+        let explanation = Explanation::new("rule").with_children(vec![target, operator]);
+
+        match decision {
+            Some(d) => explanation.with_decision(d),
+            _ => explanation,
+        }
     }
 }
 
+/// Rules keyed by their names, kept in the order they are defined.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Rules {
     #[serde(flatten)]
-    map: BTreeMap<String, Rule>,
+    map: IndexMap<String, Rule>,
 }
 
 impl Rules {
     /// Find a rule that is satisfied by the context.
     /// If multiple rules matched to the context, returns the first one.
-    pub fn find<'a, C>(&'a self, context: &'a C) -> Result<Option<(&str, &Rule)>, Error>
+    pub fn find<C>(&self, context: &C) -> Result<Option<(&str, &Rule)>, Error>
+    where
+        C: Context,
+    {
+        self.find_in(context, &Environment::SYSTEM)
+    }
+
+    /// Find a rule that is satisfied by the context in the environment, like [`Rules::find`].
+    pub fn find_in<C>(&self, context: &C, env: &Environment) -> Result<Option<(&str, &Rule)>, Error>
     where
         C: Context,
     {
-        match self.find_all(context).next() {
-            Some(Ok(r)) => Ok(Some(r)),
-            Some(Err(e)) => Err(e),
-            _ => Ok(None),
+        for (name, rule) in &self.map {
+            match rule.is_satisfied_by_in(context, env) {
+                Ok(Decision::Accept) => return Ok(Some((name, rule))),
+                Ok(_) => {}
+                Err(e) => return Err(e.in_rule(name)),
+            }
         }
+
+        Ok(None)
     }
 
     /// Find all rules that are satisfied by the context.
-    pub fn find_all<'a, C>(
+    pub fn find_all<'a, 'c, C>(
+        &'a self,
+        context: &'c C,
+    ) -> impl Iterator<Item = Result<(&'a str, &'a Rule), Error>> + 'c
+    where
+        'a: 'c,
+        C: Context,
+    {
+        self.find_all_in(context, &Environment::SYSTEM)
+    }
+
+    /// Find all rules that are satisfied by the context in the environment, like [`Rules::find_all`].
+    /// The environment is copied into the iterator, so only its clock needs to outlive the iterator.
+    pub fn find_all_in<'a, 'c, C>(
         &'a self,
-        context: &'a C,
-    ) -> impl Iterator<Item = Result<(&str, &Rule), Error>> + 'a
+        context: &'c C,
+        env: &Environment<'c>,
+    ) -> impl Iterator<Item = Result<(&'a str, &'a Rule), Error>> + 'c
     where
+        'a: 'c,
         C: Context,
     {
-        self.map
-            .iter()
-            .filter_map(|(name, rule)| match rule.is_satisfied_by(context) {
+        let env = *env;
+
+        self.map.iter().filter_map(move |(name, rule)| {
+            match rule.is_satisfied_by_in(context, &env) {
                 Ok(Decision::Accept) => Some(Ok((name.as_str(), rule))),
-                Ok(Decision::Reject) => None,
-                Err(e) => Some(Err(e)),
-            })
+                Ok(_) => None,
+                Err(e) => Some(Err(e.in_rule(name))),
+            }
+        })
+    }
+
+    /// Evaluate all rules against the context, handling errors with the policy.
+    /// Unlike [`Rules::find`], errors do not prevent the later rules from matching unless the policy is fail-fast.
+    pub fn evaluate<'a, C>(
+        &'a self,
+        context: &C,
+        policy: ErrorPolicy,
+    ) -> Result<Evaluation<'a>, Error>
+    where
+        C: Context,
+    {
+        self.evaluate_in(context, &Environment::SYSTEM, policy)
+    }
+
+    /// Evaluate all rules against the context in the environment, like [`Rules::evaluate`].
+    pub fn evaluate_in<'a, C>(
+        &'a self,
+        context: &C,
+        env: &Environment,
+        policy: ErrorPolicy,
+    ) -> Result<Evaluation<'a>, Error>
+    where
+        C: Context,
+    {
+        let mut evaluation = Evaluation::default();
+        for (name, rule) in &self.map {
+            match rule
+                .is_satisfied_by_in(context, env)
+                .map_err(|e| e.in_rule(name))
+            {
+                Ok(Decision::Accept) => evaluation.matched.push((name.as_str(), rule)),
+                Ok(_) => {}
+                Err(e) => match policy {
+                    ErrorPolicy::FailFast => return Err(e),
+                    ErrorPolicy::RejectOnError => {}
+                    ErrorPolicy::Collect => evaluation.errors.push((name.as_str(), e)),
+                },
+            }
+        }
+
+        Ok(evaluation)
+    }
+
+    /// Validate all paths and literals in the rules against the schema of the context.
+    /// All problems found in the rules are reported at once.
+    pub fn validate<C>(&self) -> Result<(), Vec<ValidationError>>
+    where
+        C: Context,
+    {
+        let schema = C::schema();
+        let mut errors = vec![];
+        for (name, rule) in &self.map {
+            let targets = rule.target.iter().map(|t| (true, t));
+            for (in_target, operator) in targets.chain([(false, &rule.operator)]) {
+                errors.extend(
+                    schema
+                        .validate(operator)
+                        .into_iter()
+                        .map(|(location, problem)| ValidationError {
+                            rule: name.clone(),
+                            in_target,
+                            location,
+                            problem,
+                        }),
+                );
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            _ => Err(errors),
+        }
+    }
+
+    /// Fold the effects of the rules into a final decision with the combining algorithm.
+    /// Rules are evaluated in the order they are defined.
+    pub fn decide<C>(&self, context: &C, combining: Combining) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        self.decide_in(context, &Environment::SYSTEM, combining)
+    }
+
+    /// Fold the effects of the rules into a final decision in the environment, like [`Rules::decide`].
+    pub fn decide_in<C>(
+        &self,
+        context: &C,
+        env: &Environment,
+        combining: Combining,
+    ) -> Result<Decision, Error>
+    where
+        C: Context,
+    {
+        combining.try_combine(self.map.iter().map(|(name, rule)| {
+            rule.is_satisfied_by_in(context, env)
+                .map(|d| rule.effect.apply(d))
+                .map_err(|e| e.in_rule(name))
+        }))
+    }
+
+    /// Compiles the rules into a plan, which makes the same decisions with less work for each context.
+    pub fn compile(&self) -> Plan<'_> {
+        Plan::new(self)
     }
 }
 
 impl Deref for Rules {
-    type Target = BTreeMap<String, Rule>;
+    type Target = IndexMap<String, Rule>;
 
     fn deref(&self) -> &Self::Target {
         &self.map
@@ -74,3 +325,203 @@ impl DerefMut for Rules {
         &mut self.map
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::environment::FixedClock;
+
+    #[test]
+    fn is_satisfied_by_target() {
+        // language=yaml
+        let yaml = r#"
+        target:
+          type: equals
+          where: '0'
+          to: abc
+        type: equals
+        where: '1'
+        to: def
+        "#;
+
+        let rule = serde_yaml::from_str::<Rule>(yaml).unwrap();
+
+        assert_eq!(
+            Decision::Accept,
+            rule.is_satisfied_by(&vec!["abc", "def"]).unwrap(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            rule.is_satisfied_by(&vec!["abc", "ghi"]).unwrap(),
+        );
+        assert_eq!(
+            Decision::NotApplicable,
+            rule.is_satisfied_by(&vec!["ghi", "def"]).unwrap(),
+        );
+        assert_eq!(
+            Decision::NotApplicable,
+            rule.is_satisfied_by(&Vec::<&str>::new()).unwrap(),
+        );
+        assert_eq!(
+            Decision::Indeterminate,
+            rule.is_satisfied_by(&"abc").unwrap(),
+        );
+    }
+
+    #[test]
+    fn evaluate() {
+        // language=yaml
+        let yaml = r#"
+        a_errored:
+          type: equals
+          where: abc
+          to: 123
+        b_matched:
+          type: equals
+          where: '0'
+          to: 123
+        c_rejected:
+          type: equals
+          where: '0'
+          to: 456
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
+        let context = vec![123];
+
+        assert!(rules.find(&context).is_err());
+        assert!(rules.evaluate(&context, ErrorPolicy::FailFast).is_err());
+
+        let evaluation = rules
+            .evaluate(&context, ErrorPolicy::RejectOnError)
+            .unwrap();
+        assert_eq!(Some("b_matched"), evaluation.first().map(|(n, _)| n));
+        assert!(!evaluation.has_errors());
+
+        let evaluation = rules.evaluate(&context, ErrorPolicy::Collect).unwrap();
+        assert_eq!(
+            vec!["b_matched"],
+            evaluation
+                .matched
+                .iter()
+                .map(|(n, _)| *n)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            vec!["a_errored"],
+            evaluation
+                .errors
+                .iter()
+                .map(|(n, _)| *n)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn decide() {
+        // language=yaml
+        let yaml = r#"
+        allow_admin:
+          type: equals
+          where: '0'
+          to: admin
+        deny_banned:
+          effect: deny
+          type: equals
+          where: '1'
+          to: banned
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
+        let decide = |context: Vec<&str>, combining| rules.decide(&context, combining).unwrap();
+
+        assert_eq!(
+            Decision::Reject,
+            decide(vec!["admin", "banned"], Combining::DenyOverrides),
+        );
+        assert_eq!(
+            Decision::Accept,
+            decide(vec!["admin", "banned"], Combining::PermitOverrides),
+        );
+        assert_eq!(
+            Decision::Accept,
+            decide(vec!["admin", "banned"], Combining::FirstApplicable),
+        );
+        assert_eq!(
+            Decision::Reject,
+            decide(vec!["user", "banned"], Combining::FirstApplicable),
+        );
+        assert_eq!(
+            Decision::NotApplicable,
+            decide(vec!["user", "active"], Combining::DenyOverrides),
+        );
+        assert_eq!(
+            Decision::Reject,
+            decide(vec!["admin", "active"], Combining::Unanimous),
+        );
+        assert_eq!(
+            Decision::Indeterminate,
+            rules.decide(&"admin", Combining::DenyOverrides).unwrap(),
+        );
+    }
+
+    #[test]
+    fn decide_in_order() {
+        // language=yaml
+        let yaml = r#"
+        deny_all:
+          effect: deny
+          type: all
+          operators: []
+        allow_admin:
+          type: equals
+          where: '0'
+          to: admin
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
+
+        assert_eq!(
+            vec!["deny_all", "allow_admin"],
+            rules.keys().map(String::as_str).collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            Decision::Reject,
+            rules
+                .decide(&vec!["admin"], Combining::FirstApplicable)
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn find_in_environment() {
+        // language=yaml
+        let yaml = r#"
+        admin:
+          type: equals
+          where: '0'
+          to: admin
+        "#;
+
+        let rules = serde_yaml::from_str::<Rules>(yaml).unwrap();
+        let context = vec!["admin"];
+        let clock = FixedClock(SystemTime::UNIX_EPOCH);
+
+        // The rules found outlive the environment, which is borrowed only while finding them.
+        let found = rules
+            .find_in(&context, &Environment::default().with_clock(&clock))
+            .unwrap();
+        let all = rules
+            .find_all_in(&context, &Environment::default().with_clock(&clock))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(Some("admin"), found.map(|(n, _)| n));
+        assert_eq!(
+            vec!["admin"],
+            all.iter().map(|(n, _)| *n).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::operator::{Location, Operator};
+use crate::value::Value;
+
+/// Static shape of a context, used to validate rules before evaluating them.
+#[derive(Debug, Clone)]
+pub enum Schema {
+    /// Any value, used when the shape is not known statically.
+    Any,
+    Boolean,
+    Integer,
+    String,
+    IpAddr,
+    #[cfg(feature = "chrono")]
+    Timestamp,
+    #[cfg(feature = "chrono")]
+    Duration,
+    Array(Box<Schema>),
+
+    /// Array of a fixed length, whose elements are addressed by their indices.
+    Tuple(Vec<Schema>),
+    Optional(Box<Schema>),
+
+    /// Map with string keys, whose entries are addressed by any key.
+    Map(Box<Schema>),
+    Struct(Vec<Field>),
+}
+
+/// Field of a struct in a schema.
+/// The schema of the field is resolved lazily, so that recursive structs can be described.
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    pub name: &'static str,
+    pub schema: fn() -> Schema,
+}
+
+/// Problem found in a path or a literal against a schema.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Problem {
+    #[error("Unknown path: {0}")]
+    UnknownPath(String),
+
+    #[error("Unexpected index in an array: {0}")]
+    UnexpectedIndex(String),
+
+    #[error("Path does not resolve to a value: {0}")]
+    NotAValue(String),
+
+    #[error("Path {path} of type {actual} is not a timestamp")]
+    NotATimestamp { path: String, actual: String },
+
+    #[error("Path {path} of type {actual} is not a version")]
+    NotAVersion { path: String, actual: String },
+
+    #[error("Path {path} of type {actual} is not an IP address")]
+    NotAnIpAddr { path: String, actual: String },
+
+    #[error("Value {actual:?} cannot be compared to {path} of type {expected}")]
+    TypeMismatch {
+        path: String,
+        expected: String,
+        actual: Value,
+    },
+}
+
+/// Problem found in a rule by validating it against a schema.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid rule {rule}: {problem} (at {}{location})", if *.in_target { "target " } else { "" })]
+pub struct ValidationError {
+    pub rule: String,
+
+    /// Whether the problem is in the target of the rule rather than its operator.
+    pub in_target: bool,
+    pub location: Location,
+    pub problem: Problem,
+}
+
+impl Schema {
+    /// Resolves the schema of the value at the path.
+    pub fn resolve<'a, I>(&self, path: I) -> Result<Schema, Problem>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let path = path.into_iter().collect::<Vec<_>>();
+        self.resolve_at(&path, 0)
+    }
+
+    fn resolve_at(&self, path: &[&str], depth: usize) -> Result<Schema, Problem> {
+        let segment = match path.get(depth) {
+            Some(s) => *s,
+            _ => return Ok(self.clone()),
+        };
+        let joined = || path[..=depth].join(".");
+
+        match self {
+            Self::Any => Ok(Self::Any),
+            Self::Boolean | Self::Integer | Self::String | Self::IpAddr => {
+                Err(Problem::UnknownPath(joined()))
+            }
+            #[cfg(feature = "chrono")]
+            Self::Timestamp | Self::Duration => Err(Problem::UnknownPath(joined())),
+            Self::Array(inner) => match usize::from_str(segment) {
+                Ok(_) => inner.resolve_at(path, depth + 1),
+                Err(_) => Err(Problem::UnexpectedIndex(joined())),
+            },
+            Self::Tuple(elements) => match usize::from_str(segment) {
+                Ok(i) => match elements.get(i) {
+                    Some(element) => element.resolve_at(path, depth + 1),
+                    _ => Err(Problem::UnknownPath(joined())),
+                },
+                Err(_) => Err(Problem::UnexpectedIndex(joined())),
+            },
+            Self::Optional(inner) => inner.resolve_at(path, depth),
+            Self::Map(inner) => inner.resolve_at(path, depth + 1),
+            Self::Struct(fields) => match fields.iter().find(|f| f.name == segment) {
+                Some(field) => (field.schema)().resolve_at(path, depth + 1),
+                _ => Err(Problem::UnknownPath(joined())),
+            },
+        }
+    }
+
+    /// Whether a value of the schema can be compared to the value.
+    pub fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Any, _) => true,
+            (Self::Optional(_), Value::Optional(None)) => true,
+            (Self::Optional(inner), v) => inner.accepts(v),
+            (s, Value::Optional(Some(v))) => s.accepts(v),
+            (Self::Boolean, Value::Boolean(_)) => true,
+            (Self::Integer, Value::Integer(_)) => true,
+            (Self::String, Value::String(_)) => true,
+            (Self::IpAddr, Value::IpAddr(_)) => true,
+            (Self::IpAddr, v @ Value::String(_)) => crate::network::to_ip_addr(&v.into()).is_some(),
+            #[cfg(feature = "chrono")]
+            (Self::Timestamp, Value::Timestamp(_)) => true,
+            #[cfg(feature = "chrono")]
+            (Self::Timestamp, v @ Value::String(_)) => {
+                crate::time::to_timestamp(&v.into()).is_some()
+            }
+            #[cfg(feature = "chrono")]
+            (Self::Duration, Value::Duration(_)) => true,
+            (Self::Array(inner), Value::Array(values)) => values.iter().all(|v| inner.accepts(v)),
+            (Self::Tuple(elements), Value::Array(values)) => {
+                elements.len() == values.len()
+                    && elements.iter().zip(values).all(|(s, v)| s.accepts(v))
+            }
+            _ => false,
+        }
+    }
+
+    /// Validates a path and a literal compared to the value at the path.
+    pub fn validate_comparison(&self, r#where: &str, value: &Value) -> Result<(), Problem> {
+        let schema = self.resolve(r#where.split('.'))?;
+        if let Self::Struct(_) | Self::Map(_) = schema {
+            return Err(Problem::NotAValue(r#where.to_string()));
+        }
+
+        if !schema.accepts(value) {
+            return Err(Problem::TypeMismatch {
+                path: r#where.to_string(),
+                expected: schema.to_string(),
+                actual: value.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the schema of the value at the path, stripping optionals.
+    fn resolve_value(&self, r#where: &str) -> Result<(Schema, Schema), Problem> {
+        let schema = self.resolve(r#where.split('.'))?;
+
+        let mut inner = schema.clone();
+        while let Self::Optional(s) = inner {
+            inner = *s;
+        }
+
+        match inner {
+            Self::Struct(_) | Self::Map(_) => Err(Problem::NotAValue(r#where.to_string())),
+            inner => Ok((schema, inner)),
+        }
+    }
+
+    /// Validates a path whose value is compared as an IP address.
+    /// Strings are accepted as well, since they are parsed as IP addresses when evaluated.
+    pub fn validate_ip_addr(&self, r#where: &str) -> Result<(), Problem> {
+        match self.resolve_value(r#where)? {
+            (_, Self::Any | Self::IpAddr | Self::String) => Ok(()),
+            (schema, _) => Err(Problem::NotAnIpAddr {
+                path: r#where.to_string(),
+                actual: schema.to_string(),
+            }),
+        }
+    }
+
+    /// Validates a path whose value is compared as a timestamp.
+    /// Strings are accepted as well, since they are parsed as RFC 3339 when evaluated.
+    #[cfg(feature = "chrono")]
+    pub fn validate_timestamp(&self, r#where: &str) -> Result<(), Problem> {
+        match self.resolve_value(r#where)? {
+            (_, Self::Any | Self::Timestamp | Self::String) => Ok(()),
+            (schema, _) => Err(Problem::NotATimestamp {
+                path: r#where.to_string(),
+                actual: schema.to_string(),
+            }),
+        }
+    }
+
+    /// Validates a path whose value is compared as a semantic version.
+    /// Only strings are accepted, since they are parsed as semantic versions when evaluated.
+    #[cfg(feature = "semver")]
+    pub fn validate_version(&self, r#where: &str) -> Result<(), Problem> {
+        match self.resolve_value(r#where)? {
+            (_, Self::Any | Self::String) => Ok(()),
+            (schema, _) => Err(Problem::NotAVersion {
+                path: r#where.to_string(),
+                actual: schema.to_string(),
+            }),
+        }
+    }
+
+    /// Validates all paths and literals in the operator, collecting the problems with their locations.
+    pub fn validate(&self, operator: &Operator) -> Vec<(Location, Problem)> {
+        let mut problems = vec![];
+        self.validate_at(operator, Location::default(), &mut problems);
+        problems
+    }
+
+    fn validate_at(
+        &self,
+        operator: &Operator,
+        location: Location,
+        problems: &mut Vec<(Location, Problem)>,
+    ) {
+        let result = match operator {
+            Operator::All(o) => {
+                for (i, operator) in o.operators.iter().enumerate() {
+                    self.validate_at(operator, location.child(i), problems);
+                }
+                Ok(())
+            }
+            Operator::Any(o) => {
+                for (i, operator) in o.operators.iter().enumerate() {
+                    self.validate_at(operator, location.child(i), problems);
+                }
+                Ok(())
+            }
+            Operator::Not(o) => {
+                self.validate_at(&o.operator, location.child(0), problems);
+                Ok(())
+            }
+            Operator::Equals(o) => self.validate_comparison(&o.r#where, &o.to),
+            Operator::NotEquals(o) => self.validate_comparison(&o.r#where, &o.to),
+            Operator::InNetwork(o) => self.validate_ip_addr(&o.r#where),
+            Operator::NotInNetwork(o) => self.validate_ip_addr(&o.r#where),
+            Operator::Bucket(o) => self.resolve_value(&o.r#where).map(|_| ()),
+            #[cfg(feature = "chrono")]
+            Operator::Before(o) => self.validate_timestamp(&o.r#where),
+            #[cfg(feature = "chrono")]
+            Operator::After(o) => self.validate_timestamp(&o.r#where),
+            #[cfg(feature = "chrono")]
+            Operator::WithinLast(o) => self.validate_timestamp(&o.r#where),
+            #[cfg(feature = "chrono")]
+            Operator::DayOfWeek(o) => self.validate_timestamp(&o.r#where),
+            #[cfg(feature = "chrono")]
+            Operator::TimeOfDay(o) => self.validate_timestamp(&o.r#where),
+            #[cfg(feature = "semver")]
+            Operator::VersionCompare(o) => self.validate_version(&o.r#where),
+            #[cfg(feature = "semver")]
+            Operator::VersionMatches(o) => self.validate_version(&o.r#where),
+        };
+
+        if let Err(problem) = result {
+            problems.push((location, problem));
+        }
+    }
+}
+
+impl Display for Schema {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Any => write!(f, "any"),
+            Self::Boolean => write!(f, "boolean"),
+            Self::Integer => write!(f, "integer"),
+            Self::String => write!(f, "string"),
+            Self::IpAddr => write!(f, "IP address"),
+            #[cfg(feature = "chrono")]
+            Self::Timestamp => write!(f, "timestamp"),
+            #[cfg(feature = "chrono")]
+            Self::Duration => write!(f, "duration"),
+            Self::Array(inner) => write!(f, "array of {}", inner),
+            Self::Tuple(elements) => write!(
+                f,
+                "tuple of ({})",
+                elements
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            Self::Optional(inner) => write!(f, "optional {}", inner),
+            Self::Map(inner) => write!(f, "map of {}", inner),
+            Self::Struct(_) => write!(f, "struct"),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate as kimari;
+    use crate::context::Context;
+
+    /// Context shared by the tests of the schemas.
+    #[derive(kimari::Context)]
+    pub(crate) struct MyContext {
+        foo: usize,
+        bar: Option<Vec<String>>,
+    }
+
+    fn schema() -> Schema {
+        MyContext::schema()
+    }
+
+    #[test]
+    fn resolve() {
+        let schema = schema();
+
+        assert!(matches!(schema.resolve(["foo"]).unwrap(), Schema::Integer));
+        assert!(matches!(
+            schema.resolve(["bar", "0"]).unwrap(),
+            Schema::String
+        ));
+        assert_eq!(
+            Problem::UnknownPath("baz".to_string()),
+            schema.resolve(["baz"]).unwrap_err(),
+        );
+        assert_eq!(
+            Problem::UnknownPath("foo.baz".to_string()),
+            schema.resolve(["foo", "baz", "qux"]).unwrap_err(),
+        );
+        assert_eq!(
+            Problem::UnexpectedIndex("bar.abc".to_string()),
+            schema.resolve(["bar", "abc"]).unwrap_err(),
+        );
+    }
+
+    #[test]
+    fn accepts() {
+        let schema = <Option<Vec<String>> as Context>::schema();
+
+        assert!(schema.accepts(&Value::Optional(None)));
+        assert!(schema.accepts(&Value::from(vec!["abc"])));
+        assert!(!schema.accepts(&Value::from(vec![123])));
+        assert!(!schema.accepts(&Value::from("abc")));
+    }
+
+    #[test]
+    fn tuple() {
+        let schema = <(usize, Option<String>) as Context>::schema();
+
+        assert!(matches!(schema.resolve(["0"]).unwrap(), Schema::Integer));
+        assert!(matches!(
+            schema.resolve(["1"]).unwrap(),
+            Schema::Optional(_)
+        ));
+        assert_eq!(
+            Problem::UnknownPath("2".to_string()),
+            schema.resolve(["2"]).unwrap_err(),
+        );
+        assert_eq!(
+            Problem::UnexpectedIndex("abc".to_string()),
+            schema.resolve(["abc"]).unwrap_err(),
+        );
+        assert!(schema.accepts(&Value::from(vec![Value::from(1), Value::Optional(None)])));
+        assert!(!schema.accepts(&Value::from(vec![1])));
+        assert_eq!("tuple of (integer, optional string)", schema.to_string());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn validate_timestamp() {
+        let schema = Schema::Struct(vec![
+            Field {
+                name: "foo",
+                schema: <usize as Context>::schema,
+            },
+            Field {
+                name: "createdAt",
+                schema: <Option<chrono::DateTime<chrono::Utc>> as Context>::schema,
+            },
+        ]);
+
+        assert_eq!(Ok(()), schema.validate_timestamp("createdAt"));
+        assert_eq!(
+            Err(Problem::NotATimestamp {
+                path: "foo".to_string(),
+                actual: "integer".to_string(),
+            }),
+            schema.validate_timestamp("foo"),
+        );
+        assert!(Schema::Timestamp.accepts(&Value::from("2024-01-01T00:00:00Z")));
+        assert!(!Schema::Timestamp.accepts(&Value::from("abc")));
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn validate_version() {
+        let schema = schema();
+
+        assert_eq!(Ok(()), schema.validate_version("bar.0"));
+        assert_eq!(
+            Err(Problem::NotAVersion {
+                path: "foo".to_string(),
+                actual: "integer".to_string(),
+            }),
+            schema.validate_version("foo"),
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Helpers for the temporal values and operators, enabled by the `chrono` feature.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, Timelike};
+use chrono_tz::Tz;
+
+use crate::decision::Decision;
+use crate::value::ValueRef;
+
+/// Resolves a timestamp from the value, parsing strings as RFC 3339.
+pub(crate) fn to_timestamp(value: &ValueRef) -> Option<DateTime<FixedOffset>> {
+    match value {
+        ValueRef::Timestamp(t) => Some(*t),
+        ValueRef::String(s) => DateTime::parse_from_rfc3339(s).ok(),
+        _ => None,
+    }
+}
+
+/// Decides a condition on the timestamp resolved from the context.
+/// If it is not a timestamp, the condition is not met.
+pub(crate) fn decide<F>(actual: &ValueRef, f: F) -> Decision
+where
+    F: FnOnce(DateTime<FixedOffset>) -> bool,
+{
+    to_timestamp(actual).map(f).unwrap_or(false).into()
+}
+
+/// Parses a duration written as a sequence of integers with units, e.g. `7d` or `1h30m`.
+/// Supported units are `w`, `d`, `h`, `m` and `s`.
+pub(crate) fn parse_duration(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration: {}", s);
+    if s.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut duration = Duration::zero();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let amount = rest[..digits].parse::<i64>().map_err(|_| invalid())?;
+        let mut chars = rest[digits..].chars();
+        let unit = match chars.next() {
+            Some('w') => Duration::try_weeks(amount),
+            Some('d') => Duration::try_days(amount),
+            Some('h') => Duration::try_hours(amount),
+            Some('m') => Duration::try_minutes(amount),
+            Some('s') => Duration::try_seconds(amount),
+            _ => None,
+        };
+
+        duration = unit
+            .and_then(|u| duration.checked_add(&u))
+            .ok_or_else(invalid)?;
+        rest = chars.as_str();
+    }
+
+    Ok(duration)
+}
+
+/// Formats the duration in the format parsed by [`parse_duration`], in the largest units possible.
+pub(crate) fn format_duration(duration: &Duration) -> String {
+    let mut seconds = duration.num_seconds();
+    if seconds == 0 {
+        return "0s".to_string();
+    }
+
+    let mut s = String::new();
+    if seconds < 0 {
+        s.push('-');
+        seconds = -seconds;
+    }
+
+    for (unit, length) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        if seconds >= length {
+            s.push_str(&format!("{}{}", seconds / length, unit));
+            seconds %= length;
+        }
+    }
+
+    s
+}
+
+/// Parses a time of day written as `HH:MM` or `HH:MM:SS`.
+pub(crate) fn parse_time_of_day(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .map_err(|_| format!("invalid time of day: {}", s))
+}
+
+/// Formats the time of day in the format parsed by [`parse_time_of_day`].
+pub(crate) fn format_time_of_day(time: &NaiveTime) -> String {
+    match time.second() {
+        0 => time.format("%H:%M").to_string(),
+        _ => time.format("%H:%M:%S").to_string(),
+    }
+}
+
+pub(crate) fn utc() -> Tz {
+    Tz::UTC
+}
+
+pub(crate) fn is_utc(tz: &Tz) -> bool {
+    *tz == Tz::UTC
+}
+
+/// (De)serializes a duration in the format of [`parse_duration`].
+pub(crate) mod duration {
+    use chrono::Duration;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&super::format_duration(duration))
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        super::parse_duration(&s).map_err(D::Error::custom)
+    }
+}
+
+/// (De)serializes a time of day in the format of [`parse_time_of_day`].
+pub(crate) mod time_of_day {
+    use chrono::NaiveTime;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&super::format_time_of_day(time))
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        super::parse_time_of_day(&s).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Value;
+
+    #[test]
+    fn duration() {
+        assert_eq!(Duration::days(7), parse_duration("7d").unwrap());
+        assert_eq!(Duration::minutes(90), parse_duration("1h30m").unwrap());
+        assert_eq!(Duration::days(14), parse_duration("2w").unwrap());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("7y").is_err());
+
+        assert_eq!("7d", format_duration(&Duration::days(7)));
+        assert_eq!("1h30m", format_duration(&Duration::minutes(90)));
+        assert_eq!("-1d1s", format_duration(&-Duration::seconds(86401)));
+        assert_eq!("0s", format_duration(&Duration::zero()));
+    }
+
+    #[test]
+    fn time_of_day() {
+        assert_eq!(
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            parse_time_of_day("09:30").unwrap(),
+        );
+        assert_eq!(
+            NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
+            parse_time_of_day("23:59:59").unwrap(),
+        );
+        assert!(parse_time_of_day("24:00").is_err());
+    }
+
+    #[test]
+    fn timestamp() {
+        let t = DateTime::parse_from_rfc3339("2024-01-01T09:00:00+09:00").unwrap();
+
+        let timestamp = |v: Value| to_timestamp(&ValueRef::from(&v));
+
+        assert_eq!(Some(t), timestamp(Value::from("2024-01-01T00:00:00Z")));
+        assert_eq!(Some(t), timestamp(Value::from(Some(t))));
+        assert_eq!(None, timestamp(Value::from("2024-01-01")));
+        assert_eq!(None, timestamp(Value::from(123)));
+    }
+}
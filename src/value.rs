@@ -1,57 +1,196 @@
-use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::num::TryFromIntError;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, TimeZone};
+use serde::de::{Error, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Value {
-    Integer(isize),
+    Boolean(bool),
+    /// Integer of any width up to 128 bits, represented losslessly.
+    Integer(#[serde(deserialize_with = "deserialize_integer")] i128),
     String(String),
     Array(Vec<Value>),
-    Optional(Option<Box<Value>>),
+
+    /// IPv4 or IPv6 address resolved from the context, serialized as a string.
+    /// Literals in rules are strings, which the network operators parse. It is not equal to any string.
+    #[serde(skip_deserializing)]
+    IpAddr(IpAddr),
+
+    /// Point in time resolved from the context, serialized in RFC 3339.
+    /// Literals in rules are strings, which the temporal operators parse. It is not equal to any string.
+    #[cfg(feature = "chrono")]
+    #[serde(skip_deserializing)]
+    Timestamp(DateTime<FixedOffset>),
+
+    /// Length of time resolved from the context, serialized like `1d12h`.
+    #[cfg(feature = "chrono")]
+    #[serde(skip_deserializing)]
+    Duration(#[serde(serialize_with = "crate::time::duration::serialize")] chrono::Duration),
+
+    /// Only `None` is deserialized, as any other values are deserialized into the other variants.
+    Optional(#[serde(deserialize_with = "deserialize_none")] Option<Box<Value>>),
 }
 
-#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
-mod bits32 {
-    use super::Value;
+/// Deserializes an integer of any width, as the untagged enum does not support 128-bit integers directly.
+fn deserialize_integer<'de, D>(deserializer: D) -> Result<i128, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct IntegerVisitor;
+
+    impl<'de> Visitor<'de> for IntegerVisitor {
+        type Value = i128;
 
-    impl From<i32> for Value {
-        fn from(value: i32) -> Self {
-            Self::from(value as isize)
+        fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+            write!(formatter, "an integer")
         }
-    }
 
-    impl From<u32> for Value {
-        fn from(value: u32) -> Self {
-            Self::from(value as isize)
+        fn visit_i64<E: Error>(self, v: i64) -> Result<i128, E> {
+            Ok(v as i128)
+        }
+
+        fn visit_i128<E: Error>(self, v: i128) -> Result<i128, E> {
+            Ok(v)
+        }
+
+        fn visit_u64<E: Error>(self, v: u64) -> Result<i128, E> {
+            Ok(v as i128)
+        }
+
+        fn visit_u128<E: Error>(self, v: u128) -> Result<i128, E> {
+            i128::try_from(v).map_err(|_| E::custom("integer out of range"))
         }
     }
+
+    deserializer.deserialize_any(IntegerVisitor)
+}
+
+fn deserialize_none<'de, D>(deserializer: D) -> Result<Option<Box<Value>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <()>::deserialize(deserializer).map(|_| None)
 }
 
-#[cfg(target_pointer_width = "64")]
-mod bits64 {
-    use super::Value;
+impl Value {
+    /// Whether the value is missing, i.e. `None`.
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Self::Optional(None))
+    }
 
-    impl From<i64> for Value {
-        fn from(value: i64) -> Self {
-            Self::from(value as isize)
+    /// Converts the value into an integer of the type, if it is an integer within the range of the type.
+    pub fn as_integer<T>(&self) -> Option<T>
+    where
+        T: TryFrom<i128>,
+    {
+        match self {
+            Self::Integer(i) => T::try_from(*i).ok(),
+            Self::Optional(Some(v)) => v.as_integer(),
+            _ => None,
         }
     }
+}
 
-    impl From<u64> for Value {
-        fn from(value: u64) -> Self {
-            Self::from(value as isize)
+/// Formats the value as written in rules, where strings are not quoted and `None` is `null`.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Boolean(b) => write!(f, "{}", b),
+            Self::Integer(i) => write!(f, "{}", i),
+            Self::String(s) => write!(f, "{}", s),
+            Self::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Self::IpAddr(a) => write!(f, "{}", a),
+            #[cfg(feature = "chrono")]
+            Self::Timestamp(t) => write!(f, "{}", t.to_rfc3339()),
+            #[cfg(feature = "chrono")]
+            Self::Duration(d) => write!(f, "{}", crate::time::format_duration(d)),
+            Self::Optional(Some(v)) => write!(f, "{}", v),
+            Self::Optional(None) => write!(f, "null"),
         }
     }
 }
 
-impl From<usize> for Value {
-    fn from(value: usize) -> Self {
-        Self::from(value as isize)
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t: ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Self::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl TryFrom<u128> for Value {
+    type Error = TryFromIntError;
+
+    fn try_from(value: u128) -> Result<Self, Self::Error> {
+        i128::try_from(value).map(Self::Integer)
+    }
+}
+
+impl From<char> for Value {
+    fn from(value: char) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> From<DateTime<Tz>> for Value
+where
+    Tz: TimeZone,
+{
+    fn from(value: DateTime<Tz>) -> Self {
+        Self::Timestamp(value.fixed_offset())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::Duration> for Value {
+    fn from(value: chrono::Duration) -> Self {
+        Self::Duration(value)
     }
 }
 
-impl From<isize> for Value {
-    fn from(value: isize) -> Self {
-        Self::Integer(value)
+impl From<IpAddr> for Value {
+    fn from(value: IpAddr) -> Self {
+        Self::IpAddr(value)
+    }
+}
+
+impl From<Ipv4Addr> for Value {
+    fn from(value: Ipv4Addr) -> Self {
+        Self::IpAddr(value.into())
+    }
+}
+
+impl From<Ipv6Addr> for Value {
+    fn from(value: Ipv6Addr) -> Self {
+        Self::IpAddr(value.into())
     }
 }
 
@@ -107,6 +246,11 @@ where
 impl PartialEq<Self> for Value {
     fn eq(&self, other: &Self) -> bool {
         match self {
+            Self::Boolean(a) => match other {
+                Self::Boolean(b) => a == b,
+                Self::Optional(b) => self == b,
+                _ => false,
+            },
             Self::Integer(a) => match other {
                 Self::Integer(b) => a == b,
                 Self::Optional(b) => self == b,
@@ -121,6 +265,23 @@ impl PartialEq<Self> for Value {
                 Self::Array(b) => a == b,
                 _ => false,
             },
+            Self::IpAddr(a) => match other {
+                Self::IpAddr(b) => a == b,
+                Self::Optional(b) => self == b,
+                _ => false,
+            },
+            #[cfg(feature = "chrono")]
+            Self::Timestamp(a) => match other {
+                Self::Timestamp(b) => a == b,
+                Self::Optional(b) => self == b,
+                _ => false,
+            },
+            #[cfg(feature = "chrono")]
+            Self::Duration(a) => match other {
+                Self::Duration(b) => a == b,
+                Self::Optional(b) => self == b,
+                _ => false,
+            },
             Self::Optional(a) => match other {
                 Self::Optional(b) => a == b,
                 _ => a.as_deref().map(|a| a == other).unwrap_or(false),
@@ -137,6 +298,167 @@ impl PartialEq<Option<Box<Self>>> for Value {
 
 impl Eq for Value {}
 
+/// Value borrowed from a context, so that operators compare it without cloning strings and arrays.
+///
+/// Strings are borrowed where the context holds them, and owned where they are computed, e.g. from a version.
+/// Arrays collect the elements borrowed. `Some` of an optional value is represented as the value itself.
+#[derive(Debug, Clone)]
+pub enum ValueRef<'a> {
+    Boolean(bool),
+    Integer(i128),
+    String(Cow<'a, str>),
+    Array(Vec<ValueRef<'a>>),
+    IpAddr(IpAddr),
+    #[cfg(feature = "chrono")]
+    Timestamp(DateTime<FixedOffset>),
+    #[cfg(feature = "chrono")]
+    Duration(chrono::Duration),
+
+    /// Missing value, i.e. `None`.
+    Missing,
+}
+
+impl ValueRef<'_> {
+    /// Whether the value is missing, i.e. `None`.
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Self::Missing)
+    }
+}
+
+impl<'a> From<&'a Value> for ValueRef<'a> {
+    fn from(value: &'a Value) -> Self {
+        match value {
+            Value::Boolean(b) => Self::Boolean(*b),
+            Value::Integer(i) => Self::Integer(*i),
+            Value::String(s) => Self::String(Cow::Borrowed(s)),
+            Value::Array(values) => Self::Array(values.iter().map(Self::from).collect()),
+            Value::IpAddr(a) => Self::IpAddr(*a),
+            #[cfg(feature = "chrono")]
+            Value::Timestamp(t) => Self::Timestamp(*t),
+            #[cfg(feature = "chrono")]
+            Value::Duration(d) => Self::Duration(*d),
+            Value::Optional(Some(v)) => Self::from(&**v),
+            Value::Optional(None) => Self::Missing,
+        }
+    }
+}
+
+impl From<Value> for ValueRef<'static> {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Boolean(b) => Self::Boolean(b),
+            Value::Integer(i) => Self::Integer(i),
+            Value::String(s) => Self::String(Cow::Owned(s)),
+            Value::Array(values) => Self::Array(values.into_iter().map(Self::from).collect()),
+            Value::IpAddr(a) => Self::IpAddr(a),
+            #[cfg(feature = "chrono")]
+            Value::Timestamp(t) => Self::Timestamp(t),
+            #[cfg(feature = "chrono")]
+            Value::Duration(d) => Self::Duration(d),
+            Value::Optional(Some(v)) => Self::from(*v),
+            Value::Optional(None) => Self::Missing,
+        }
+    }
+}
+
+/// Clones the borrowed parts of the value, e.g. to hold it in an explanation.
+impl From<ValueRef<'_>> for Value {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Boolean(b) => Self::Boolean(b),
+            ValueRef::Integer(i) => Self::Integer(i),
+            ValueRef::String(s) => Self::String(s.into_owned()),
+            ValueRef::Array(values) => Self::Array(values.into_iter().map(Self::from).collect()),
+            ValueRef::IpAddr(a) => Self::IpAddr(a),
+            #[cfg(feature = "chrono")]
+            ValueRef::Timestamp(t) => Self::Timestamp(t),
+            #[cfg(feature = "chrono")]
+            ValueRef::Duration(d) => Self::Duration(d),
+            ValueRef::Missing => Self::Optional(None),
+        }
+    }
+}
+
+macro_rules! impl_from_integer_ref {
+    ($($t: ty),*) => {
+        $(
+            impl From<&$t> for ValueRef<'_> {
+                fn from(value: &$t) -> Self {
+                    Self::Integer(*value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer_ref!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<&bool> for ValueRef<'_> {
+    fn from(value: &bool) -> Self {
+        Self::Boolean(*value)
+    }
+}
+
+impl From<&char> for ValueRef<'_> {
+    fn from(value: &char) -> Self {
+        Self::String(Cow::Owned(value.to_string()))
+    }
+}
+
+impl<'a> From<&'a String> for ValueRef<'a> {
+    fn from(value: &'a String) -> Self {
+        Self::String(Cow::Borrowed(value))
+    }
+}
+
+impl<'a> From<&'a str> for ValueRef<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::String(Cow::Borrowed(value))
+    }
+}
+
+impl From<&IpAddr> for ValueRef<'_> {
+    fn from(value: &IpAddr) -> Self {
+        Self::IpAddr(*value)
+    }
+}
+
+impl From<&Ipv4Addr> for ValueRef<'_> {
+    fn from(value: &Ipv4Addr) -> Self {
+        Self::IpAddr((*value).into())
+    }
+}
+
+impl From<&Ipv6Addr> for ValueRef<'_> {
+    fn from(value: &Ipv6Addr) -> Self {
+        Self::IpAddr((*value).into())
+    }
+}
+
+/// Compares the value from the context to a value in rules, the same as [`Value`] compares.
+impl PartialEq<Value> for ValueRef<'_> {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Self::Missing, Value::Optional(b)) => b.is_none(),
+            (Self::Missing, _) => false,
+            (Self::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a == b)
+            }
+            (Self::Array(_), _) => false,
+            (_, Value::Optional(b)) => b.as_deref().map(|b| self == b).unwrap_or(false),
+            (Self::Boolean(a), Value::Boolean(b)) => a == b,
+            (Self::Integer(a), Value::Integer(b)) => a == b,
+            (Self::String(a), Value::String(b)) => a == b,
+            (Self::IpAddr(a), Value::IpAddr(b)) => a == b,
+            #[cfg(feature = "chrono")]
+            (Self::Timestamp(a), Value::Timestamp(b)) => a == b,
+            #[cfg(feature = "chrono")]
+            (Self::Duration(a), Value::Duration(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,6 +467,7 @@ mod tests {
     fn deserialize() {
         // language=yaml
         let yaml = r#"
+        - true
         - 123
         - '123'
         - abc
@@ -156,6 +479,7 @@ mod tests {
 
         assert_eq!(
             Value::from(vec![
+                Value::from(true),
                 Value::from(123),
                 Value::from("123"),
                 Value::from("abc"),
@@ -166,6 +490,52 @@ mod tests {
         )
     }
 
+    #[test]
+    fn display() {
+        assert_eq!("eu", Value::from("eu").to_string());
+        assert_eq!(
+            "[1, true]",
+            Value::Array(vec![Value::from(1), Value::from(true)]).to_string()
+        );
+        assert_eq!("null", Value::from(None::<i32>).to_string());
+        assert_eq!(
+            "10.0.0.1",
+            Value::from(Ipv4Addr::new(10, 0, 0, 1)).to_string()
+        );
+    }
+
+    #[test]
+    fn integer_lossless() {
+        assert_eq!(Value::Integer(u64::MAX as i128), Value::from(u64::MAX));
+        assert_ne!(Value::from(u64::MAX), Value::from(-1));
+        assert_eq!(Value::from(i64::MIN), Value::from(i64::MIN as i128));
+        assert_eq!(Value::from(123_u8), Value::from(123_i64));
+        assert!(Value::try_from(u128::MAX).is_err());
+
+        assert_eq!(Some(u64::MAX), Value::from(u64::MAX).as_integer::<u64>());
+        assert_eq!(None, Value::from(u64::MAX).as_integer::<i64>());
+        assert_eq!(None, Value::from(-1).as_integer::<usize>());
+        assert_eq!(Some(255_u8), Value::from(Some(255)).as_integer::<u8>());
+        assert_eq!(None, Value::from("abc").as_integer::<u8>());
+    }
+
+    #[test]
+    fn deserialize_u64() {
+        let value = serde_yaml::from_str::<Value>("18446744073709551615").unwrap();
+
+        assert_eq!(Value::from(u64::MAX), value);
+        assert_eq!(
+            "18446744073709551615",
+            serde_json::to_string(&value).unwrap(),
+        );
+    }
+
+    #[test]
+    fn deserialize_unsupported() {
+        assert!(serde_yaml::from_str::<Value>("1.5").is_err());
+        assert!(serde_yaml::from_str::<Value>("foo: bar").is_err());
+    }
+
     #[test]
     fn eq_string_string() {
         assert_eq!(Value::from("abc"), Value::from("abc"));
@@ -195,6 +565,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eq_ip_addr() {
+        let ip = IpAddr::from(Ipv6Addr::LOCALHOST);
+
+        // Addresses are not equal to the strings they are parsed from, which only the network operators parse.
+        assert_eq!(Value::from(ip), Value::from(Some(ip)));
+        assert_ne!(Value::from(ip), Value::from("::1"));
+        assert_ne!(Value::from("0:0::1"), Value::from(Some(ip)));
+        assert_ne!(Value::from(ip), Value::from(Ipv4Addr::LOCALHOST));
+        assert_eq!("\"::1\"", serde_json::to_string(&Value::from(ip)).unwrap());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn eq_timestamp() {
+        let t = DateTime::parse_from_rfc3339("2024-01-01T09:00:00+09:00").unwrap();
+
+        // Timestamps are not equal to the strings they are parsed from, which only the temporal operators parse.
+        assert_eq!(
+            Value::from(t),
+            Value::from(Some(
+                DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap()
+            )),
+        );
+        assert_ne!(Value::from(t), Value::from("2024-01-01T00:00:00Z"));
+        assert_ne!(Value::from("2024-01-01T00:00:00Z"), Value::from(Some(t)));
+        assert_ne!(ValueRef::from("2024-01-01T00:00:00Z"), Value::from(t),);
+        assert_eq!(
+            "\"2024-01-01T09:00:00+09:00\"",
+            serde_json::to_string(&Value::from(t)).unwrap(),
+        );
+        assert_eq!(
+            "\"1d12h\"",
+            serde_json::to_string(&Value::from(chrono::Duration::hours(36))).unwrap(),
+        );
+    }
+
+    #[test]
+    fn eq_value_ref() {
+        let ip = IpAddr::from(Ipv6Addr::LOCALHOST);
+
+        assert_eq!(ValueRef::from("abc"), Value::from("abc"));
+        assert_eq!(ValueRef::from("abc"), Value::from(Some("abc")));
+        assert_eq!(ValueRef::from(&Value::from(Some(123))), Value::from(123));
+        assert_eq!(ValueRef::Missing, Value::from(None::<String>));
+        assert_ne!(ValueRef::Missing, Value::from("abc"));
+        assert_ne!(ValueRef::from("abc"), Value::from(None::<String>));
+        assert_eq!(ValueRef::from(&ip), Value::from(ip));
+        assert_ne!(ValueRef::from(&ip), Value::from("::1"));
+        assert_ne!(ValueRef::from("0:0::1"), Value::from(ip));
+        assert_eq!(
+            ValueRef::from(&Value::from(vec![Some(123), None])),
+            Value::from(vec![Value::from(123), Value::Optional(None)]),
+        );
+        assert_ne!(
+            ValueRef::from(&Value::from(vec![123])),
+            Value::from(vec![123, 456]),
+        );
+        assert_eq!(
+            Value::from(vec![Some("abc")]),
+            Value::from(ValueRef::from(&Value::from(vec![Some("abc")]))),
+        );
+    }
+
     #[test]
     fn eq_optional() {
         assert_eq!(Value::from(None::<String>), Value::from(None::<String>));
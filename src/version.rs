@@ -0,0 +1,23 @@
+//! Helpers for the semantic version operators, enabled by the `semver` feature.
+
+use semver::Version;
+
+use crate::decision::Decision;
+use crate::value::ValueRef;
+
+/// Resolves a version from the value, parsing strings as semantic versions.
+pub(crate) fn to_version(value: &ValueRef) -> Option<Version> {
+    match value {
+        ValueRef::String(s) => Version::parse(s).ok(),
+        _ => None,
+    }
+}
+
+/// Decides a condition on the version resolved from the context.
+/// If it is not a semantic version, the condition is not met.
+pub(crate) fn decide<F>(actual: &ValueRef, f: F) -> Decision
+where
+    F: FnOnce(Version) -> bool,
+{
+    to_version(actual).map(f).unwrap_or(false).into()
+}